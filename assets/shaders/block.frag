@@ -5,9 +5,9 @@ layout (location = 2) out vec4 gAlbedoSpec;
 
 in vec3 pos0;
 in vec3 normal0;
-in vec2 uv0;
+in vec3 uv0;
 
-uniform sampler2D tex;
+uniform sampler2DArray tex;
 void main()
 {
     gPosition = pos0;
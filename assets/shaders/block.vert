@@ -3,8 +3,9 @@
 layout (location = 0) in vec3 pos;
 layout (location = 1) in vec2 uv;
 layout (location = 2) in vec3 normal;
+layout (location = 3) in float layer;
 
-out vec2 uv0;
+out vec3 uv0;
 out vec3 normal0;
 out vec3 pos0;
 
@@ -15,7 +16,7 @@ void main()
 {
     vec4 worldPos = model * vec4(pos, 1.0);
     pos0 = worldPos.xyz;
-    uv0 = uv;
+    uv0 = vec3(uv, layer);
 
     mat3 normalMatrix = transpose(inverse(mat3(model)));
     normal0 = normalMatrix * normal;
@@ -0,0 +1,21 @@
+#version 330 core
+out vec4 color;
+
+in vec2 uv0;
+
+uniform sampler2D image;
+uniform bool horizontal;
+
+const float weights[5] = float[] (0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+void main()
+{
+    vec2 texelSize = 1.0 / textureSize(image, 0);
+    vec2 direction = horizontal ? vec2(texelSize.x, 0.0) : vec2(0.0, texelSize.y);
+    vec3 result = texture(image, uv0).rgb * weights[0];
+    for (int i = 1; i < 5; ++i)
+    {
+        result += texture(image, uv0 + direction * i).rgb * weights[i];
+        result += texture(image, uv0 - direction * i).rgb * weights[i];
+    }
+    color = vec4(result, 1.0);
+}
\ No newline at end of file
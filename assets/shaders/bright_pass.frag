@@ -0,0 +1,16 @@
+#version 330 core
+out vec4 color;
+
+in vec2 uv0;
+
+uniform sampler2D scene;
+uniform float threshold;
+void main()
+{
+    vec3 hdr = texture(scene, uv0).rgb;
+    float brightness = dot(hdr, vec3(0.2126, 0.7152, 0.0722));
+    if (brightness > threshold)
+        color = vec4(hdr, 1.0);
+    else
+        color = vec4(0.0, 0.0, 0.0, 1.0);
+}
\ No newline at end of file
@@ -6,6 +6,8 @@ in vec2 uv0;
 uniform sampler2D gPosition;
 uniform sampler2D gNormal;
 uniform sampler2D gAlbedoSpec;
+uniform sampler2D ssao;
+uniform bool ssaoEnabled;
 
 struct Light {
     vec3 position;
@@ -18,13 +20,17 @@ struct Light {
 const int NR_LIGHTS = 32;
 uniform Light lights[NR_LIGHTS];
 uniform vec3 viewPos;
+uniform vec3 fogColor;
+uniform float fogDensity;
 void main()
 {
     vec3 pos = texture(gPosition, uv0).rgb;
     vec3 normal = texture(gNormal, uv0).rgb;
     vec3 albedo = texture(gAlbedoSpec, uv0).rgb;
 
-    vec3 lighting = albedo * 0.1;
+    float occlusion = ssaoEnabled ? texture(ssao, uv0).r : 1.0;
+
+    vec3 lighting = albedo * 0.1 * occlusion;
     vec3 viewDir = normalize(viewPos - pos);
     for (int i = 0; i < NR_LIGHTS; ++i)
     {
@@ -39,5 +45,12 @@ void main()
         }
     }
 
+    if (length(normal) > 0.0)
+    {
+        float fogAmount = fogDensity * length(viewPos - pos);
+        float fogFactor = clamp(1.0 - exp(-fogAmount * fogAmount), 0.0, 1.0);
+        lighting = mix(lighting, fogColor, fogFactor);
+    }
+
     color = vec4(lighting, 1.0);
 }
\ No newline at end of file
@@ -0,0 +1,52 @@
+#version 330 core
+out float occlusion;
+
+in vec2 uv0;
+
+uniform sampler2D gPosition;
+uniform sampler2D gNormal;
+uniform sampler2D noise;
+
+const int MAX_KERNEL_SIZE = 64;
+uniform vec3 samples[MAX_KERNEL_SIZE];
+uniform int kernelSize;
+uniform float radius;
+uniform float bias;
+uniform vec2 noiseScale;
+
+uniform mat4 view;
+uniform mat4 projection;
+void main()
+{
+    vec3 worldNormal = texture(gNormal, uv0).rgb;
+    if (length(worldNormal) == 0.0)
+    {
+        occlusion = 1.0;
+        return;
+    }
+
+    vec3 fragPos = (view * vec4(texture(gPosition, uv0).xyz, 1.0)).xyz;
+    vec3 normal = normalize(mat3(view) * worldNormal);
+    vec3 randomVec = normalize(texture(noise, uv0 * noiseScale).xyz);
+    vec3 tangent = normalize(randomVec - normal * dot(randomVec, normal));
+    vec3 bitangent = cross(normal, tangent);
+    mat3 TBN = mat3(tangent, bitangent, normal);
+
+    float occluded = 0.0;
+    for (int i = 0; i < kernelSize; ++i)
+    {
+        vec3 samplePos = fragPos + TBN * samples[i] * radius;
+        vec4 offset = projection * vec4(samplePos, 1.0);
+        offset.xyz /= offset.w;
+        offset.xyz = offset.xyz * 0.5 + 0.5;
+
+        if (length(texture(gNormal, offset.xy).rgb) == 0.0)
+            continue;
+
+        float sampleDepth = (view * vec4(texture(gPosition, offset.xy).xyz, 1.0)).z;
+        float rangeCheck = smoothstep(0.0, 1.0, radius / abs(fragPos.z - sampleDepth));
+        occluded += (sampleDepth >= samplePos.z + bias ? 1.0 : 0.0) * rangeCheck;
+    }
+
+    occlusion = 1.0 - (occluded / kernelSize);
+}
\ No newline at end of file
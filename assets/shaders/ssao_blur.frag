@@ -0,0 +1,20 @@
+#version 330 core
+out float color;
+
+in vec2 uv0;
+
+uniform sampler2D occlusion;
+void main()
+{
+    vec2 texelSize = 1.0 / vec2(textureSize(occlusion, 0));
+    float result = 0.0;
+    for (int x = -2; x < 2; ++x)
+    {
+        for (int y = -2; y < 2; ++y)
+        {
+            vec2 offset = vec2(float(x), float(y)) * texelSize;
+            result += texture(occlusion, uv0 + offset).r;
+        }
+    }
+    color = result / 16.0;
+}
\ No newline at end of file
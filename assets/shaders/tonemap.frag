@@ -0,0 +1,15 @@
+#version 330 core
+out vec4 color;
+
+in vec2 uv0;
+
+uniform sampler2D scene;
+uniform sampler2D bloom;
+uniform float exposure;
+uniform float bloomStrength;
+void main()
+{
+    vec3 hdr = texture(scene, uv0).rgb + texture(bloom, uv0).rgb * bloomStrength;
+    vec3 mapped = vec3(1.0) - exp(-hdr * exposure);
+    color = vec4(mapped, 1.0);
+}
\ No newline at end of file
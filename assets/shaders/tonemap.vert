@@ -0,0 +1,11 @@
+#version 330 core
+layout (location = 0) in vec3 pos;
+layout (location = 1) in vec2 uv;
+
+out vec2 uv0;
+
+void main()
+{
+    uv0 = uv;
+    gl_Position = vec4(pos, 1.0);
+}
\ No newline at end of file
@@ -2,11 +2,13 @@
 
 out vec4 color;
 
-in vec2 uv0;
+in vec3 uv0;
 
 uniform vec3 lightColor;
-uniform sampler2D tex;
+uniform float emission;
+uniform sampler2DArray tex;
 void main()
 {
-    color = texture(tex, uv0);
+    vec4 texel = texture(tex, uv0);
+    color = vec4(texel.rgb + texel.rgb * lightColor * emission, texel.a);
 }
\ No newline at end of file
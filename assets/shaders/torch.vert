@@ -3,14 +3,15 @@
 layout (location = 0) in vec3 pos;
 layout (location = 1) in vec2 uv;
 layout (location = 2) in vec3 normal;
+layout (location = 3) in float layer;
 
-out vec2 uv0;
+out vec3 uv0;
 
 uniform mat4 model;
 uniform mat4 view;
 uniform mat4 projection;
 void main()
 {
-    uv0 = uv;
+    uv0 = vec3(uv, layer);
     gl_Position = projection * view * model * vec4(pos, 1.0);
 }
\ No newline at end of file
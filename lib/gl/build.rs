@@ -13,7 +13,8 @@ fn main() {
     let mut file_gl = File::create(&Path::new(&out_dir).join("bindings.rs")).unwrap();
 
     let reg = Registry::new(Api::Gl, (4, 5), Profile::Core, Fallbacks::All, [
-        "GL_NV_command_list"
+        "GL_NV_command_list",
+        "GL_EXT_texture_filter_anisotropic"
     ]);
 
     reg.write_bindings(ProfilingStructGenerator, &mut file_gl).unwrap();
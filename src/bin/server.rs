@@ -0,0 +1,17 @@
+extern crate minecraft;
+
+use minecraft::server::properties::ServerProperties;
+use minecraft::server::server::Server;
+use std::path::PathBuf;
+
+fn main() {
+    let path = ::std::env::args().nth(1).map(PathBuf::from).unwrap_or(PathBuf::from("server.properties"));
+    let result = ServerProperties::load(&path).and_then(Server::start);
+    match result {
+        Ok(mut server) => server.run(),
+        Err(e) => {
+            println!("Failed to start server: {}", e);
+            ::std::process::exit(1);
+        }
+    }
+}
\ No newline at end of file
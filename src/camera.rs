@@ -14,6 +14,7 @@ impl Camera {
             front,
             right,
             up,
+            eye_offset: vec3(0.0, 0.0, 0.0),
             rotation: vec2(0.0, 0.0),
             mouse_pos: vec2(0.0, 0.0),
             mouse_speed: 0.05,
@@ -27,22 +28,44 @@ impl Camera {
         self.update_mouse(mouse_x, mouse_y);
 
         self.position = self.position + *velocity;
+        self.eye_offset = *height_vector;
 
         self.view = ext::look_at(self.position + *height_vector, self.position + self.front + *height_vector, self.up);
     }
 
+    pub fn interpolate(&mut self, previous: &Vec3, alpha: f32) {
+        let eye = *previous + (self.position - *previous) * alpha + self.eye_offset;
+        self.view = ext::look_at(eye, eye + self.front, self.up);
+    }
+
     pub fn update_mouse(&mut self, mouse_x: f64, mouse_y: f64) {
         let x = mouse_x as f32;
         let y = mouse_y as f32;
 
         if self.mouse_pos.x == x && self.mouse_pos.y == y { return; }
 
-        let new_x = (x - self.mouse_pos.x) * self.mouse_speed;
-        let new_y = (self.mouse_pos.y - y) * self.mouse_speed;
+        let delta = vec2((x - self.mouse_pos.x) * self.mouse_speed, (self.mouse_pos.y - y) * self.mouse_speed);
+        self.look(delta);
 
-        self.rotation.x += new_x;
-        self.rotation.y += new_y;
+        self.mouse_pos.x = x;
+        self.mouse_pos.y = y;
+    }
+
+    pub fn look(&mut self, delta: Vec2) {
+        if delta.x == 0.0 && delta.y == 0.0 { return; }
 
+        self.rotation.x += delta.x;
+        self.rotation.y += delta.y;
+        self.update_vectors();
+    }
+
+    pub fn set_rotation(&mut self, yaw: f32, pitch: f32) {
+        self.rotation = vec2(yaw, pitch);
+        self.update_vectors();
+        self.view = ext::look_at(self.position, self.position + self.front, self.up);
+    }
+
+    fn update_vectors(&mut self) {
         if self.rotation.y > 89.9 { self.rotation.y = 89.9; }
         else if self.rotation.y < -89.9 { self.rotation.y = -89.9; }
 
@@ -56,13 +79,16 @@ impl Camera {
         ));
         self.right = normalize(cross(self.front, *WORLD_UP));
         self.up = normalize(cross(self.right, self.front));
+    }
 
-        self.mouse_pos.x = x;
-        self.mouse_pos.y = y;
+    pub fn set_fov(&mut self, fov: f32, screen_size: (i32, i32)) {
+        self.projection = ext::perspective(radians(fov), screen_size.0 as f32/screen_size.1 as f32, 0.1, 1000.0);
     }
 
+    pub fn set_mouse_speed(&mut self, speed: f32) { self.mouse_speed = speed; }
     pub fn set_position(&mut self, pos: Vec3) { self.position = pos; }
     pub fn position(&self) -> &Vec3 { &self.position }
+    pub fn rotation(&self) -> &Vec2 { &self.rotation }
     pub fn forward(&self) -> &Vec3 { &self.front }
     pub fn right(&self) -> &Vec3 { &self.right }
     pub fn model(&self) -> &Mat4 { &self.model }
@@ -75,6 +101,7 @@ pub struct Camera {
     front: Vec3,
     right: Vec3,
     up: Vec3,
+    eye_offset: Vec3,
 
     rotation: Vec2,
     mouse_pos: Vec2,
@@ -1,34 +1,333 @@
+use glfw::Key;
 use camera::Camera;
 use player::Player;
+use render::capture;
+use render::hdr_buffer::HdrBuffer;
 use shader;
-use util::text::Text;
-use util::{math::vec3, timer::Timer};
+use ui::text::Text;
+use ui::message_log::MessageLog;
+use util::{math::{vec3, Vec3, Vec3i}, timer::{Timer, TICK_RATE}};
 use window::Window;
-use world::block::block_type::BlockType;
-use world::scenery::lighting::Lighting;
-use world::scenery::skybox::SkyBox;
+use world::block::block_database;
+use world::block::block_state::BlockState;
+use world::block::block_type::{BlockType, keys};
+use render::lighting::Lighting;
+use render::skybox::SkyBox;
 use world::world::World;
+use render::world_renderer::WorldRenderer;
 use GL::Gl;
+use std::path::{Path, PathBuf};
+use ui::chat::Chat;
+use input::events::InputQueue;
+use input::controls::{Controls, KeyAction, KeyState};
+use input::gamepad::Gamepad;
+use util::noise;
+use input::recording::{Recorder, RecordedFrame, RecordingHeader, Replay, RECORDING_VERSION};
+use settings::{Settings, SettingsStore};
+use minecraft::net::client::{Client, ClientEvent};
+use minecraft::net::protocol::DEFAULT_PORT;
+
+const SCREENSHOT_DIRECTORY: &'static str = "./screenshots";
+const EXPOSURE_STEP: f32 = 0.1;
+const BLOOM_STEP: f32 = 0.1;
+const REACH: f32 = 6.0;
+const HEADLESS_LOAD_DISTANCE: i32 = 8;
+
+pub struct Headless {
+    pub frames: u32,
+    pub output: PathBuf,
+    pub position: Option<Vec3>,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub yaw_step: f32,
+    pub g_buffer: bool
+}
+
+impl Headless {
+    pub fn new(frames: u32) -> Headless {
+        Headless {
+            frames,
+            output: PathBuf::from("./captures"),
+            position: None,
+            yaw: 0.0,
+            pitch: 0.0,
+            yaw_step: 0.0,
+            g_buffer: false
+        }
+    }
+
+    fn position_camera(&self, camera: &mut Camera, frame: u32) {
+        let yaw = (self.yaw + self.yaw_step * frame as f32) % 360.0;
+        camera.set_rotation(if yaw < 0.0 { yaw + 360.0 } else { yaw }, self.pitch);
+    }
+
+    fn capture(&self, window: &Window, gl: &Gl, lighting: &Lighting, frame: u32) -> Result<(), String> {
+        capture_frame(window, gl, lighting, &self.output, &format!("frame_{:04}", frame), self.g_buffer)
+    }
+}
+
+fn capture_frame(window: &Window, gl: &Gl, lighting: &Lighting, directory: &Path, name: &str, g_buffer: bool) -> Result<(), String> {
+    let size = window.framebuffer_size();
+    let pixels = capture::read_framebuffer(gl, size);
+    capture::save_png(&directory.join(format!("{}.png", name)), size, &pixels)?;
+    if g_buffer {
+        lighting.capture_g_buffer(directory, name)?;
+    }
+    Ok(())
+}
+
+pub enum InputSource {
+    Live,
+    Record(PathBuf),
+    Replay(PathBuf)
+}
+
+pub struct Connect {
+    pub address: String,
+    pub name: String
+}
+
+fn connect(connect: &Connect) -> Result<Client, String> {
+    let address = if connect.address.contains(':') {
+        connect.address.clone()
+    } else {
+        format!("{}:{}", connect.address, DEFAULT_PORT)
+    };
+    let client = Client::connect(&address, &connect.name)?;
+    println!("Connected to {} as {}", client.address(), connect.name);
+    Ok(client)
+}
+
+fn handle_client_events(client: &mut Client, world: &mut World, player: &mut Player, camera: &mut Camera,
+                        previous_position: &mut Vec3, messages: &mut MessageLog, time: u64) {
+    for event in client.poll(world) {
+        match event {
+            ClientEvent::Chat(message) => messages.push(&message, vec3(1.0, 1.0, 1.0), time),
+            ClientEvent::Correction(position) => {
+                camera.set_position(position);
+                player.set_position(&position);
+                *previous_position = position;
+            },
+            ClientEvent::Disconnected(reason) => {
+                println!("{}", reason);
+                messages.push(&reason, vec3(1.0, 0.4, 0.4), time);
+            }
+        }
+    }
+}
+
+fn wait_for_chunks(world: &mut World, client: &mut Option<Client>, position: &Vec3, distance: i32) {
+    world.build_chunks(position);
+    while !world.is_loaded_within(position, distance) {
+        if let Some(ref mut c) = *client {
+            if !c.is_connected() { return; }
+            for event in c.poll(world) {
+                if let ClientEvent::Disconnected(reason) = event {
+                    println!("{}", reason);
+                }
+            }
+            world.build_chunks(position);
+        }
+        world.take_chunk_from_queue();
+    }
+}
+
+fn open_input_source(source: InputSource, settings: &Settings) -> (Option<Recorder>, Option<Replay>) {
+    match source {
+        InputSource::Live => (None, None),
+        InputSource::Record(path) => {
+            let header = RecordingHeader {
+                version: RECORDING_VERSION,
+                tick_rate: TICK_RATE,
+                seed: settings.world.seed,
+                controls: settings.controls.clone()
+            };
+            match Recorder::create(&path, &header) {
+                Ok(recorder) => {
+                    println!("Recording input to {}", path.display());
+                    (Some(recorder), None)
+                },
+                Err(e) => {
+                    println!("{}", e);
+                    (None, None)
+                }
+            }
+        },
+        InputSource::Replay(path) => match Replay::load(&path) {
+            Ok(replay) => {
+                println!("Replaying {} frames from {} with seed {}", replay.len(), path.display(), replay.header().seed);
+                (None, Some(replay))
+            },
+            Err(e) => {
+                println!("Failed to load replay: {}", e);
+                (None, None)
+            }
+        }
+    }
+}
+
+fn adjust_post_processing(player: &Player, settings: &mut SettingsStore) -> bool {
+    let clicked = |action: KeyAction| player.controls().key_state(action) == &KeyState::Clicked;
+    let actions = [KeyAction::ToggleSsao, KeyAction::SsaoQuality, KeyAction::ExposureUp,
+        KeyAction::ExposureDown, KeyAction::BloomUp, KeyAction::BloomDown];
+    if !actions.iter().any(|&action| clicked(action)) { return false; }
+
+    settings.update(|s| {
+        if clicked(KeyAction::ToggleSsao) { s.video.ssao = !s.video.ssao; }
+        if clicked(KeyAction::SsaoQuality) { s.video.ssao_quality = s.video.ssao_quality.next(); }
+        if clicked(KeyAction::ExposureUp) { s.video.exposure += EXPOSURE_STEP; }
+        if clicked(KeyAction::ExposureDown) { s.video.exposure -= EXPOSURE_STEP; }
+        if clicked(KeyAction::BloomUp) { s.video.bloom_strength += BLOOM_STEP; }
+        if clicked(KeyAction::BloomDown) { s.video.bloom_strength -= BLOOM_STEP; }
+    });
+    true
+}
+
+fn apply_video_settings(settings: &Settings, hdr: &mut HdrBuffer, lighting: &mut Lighting) {
+    lighting.set_ssao(settings.video.ssao, settings.video.ssao_quality);
+    hdr.set_exposure(settings.video.exposure);
+    hdr.set_bloom_strength(settings.video.bloom_strength);
+}
+
+fn place_block(player: &Player, camera: &Camera, world: &mut World) -> Option<(Vec3i, BlockState)> {
+    let eye = player.position() + player.height_vector();
+    if let Some((hit, normal)) = world.raycast(&eye, camera.forward(), REACH) {
+        let target = hit + normal;
+        if world.block_at(&target).block_type != BlockType::AIR { return None; }
+        let block = match player.selected_block() {
+            Some(block_type) => block_database::get().get_block(block_type),
+            None => return None
+        };
+        let state = BlockState::placed(&block, camera.forward(), &normal);
+        if world.set_block(&target, state) {
+            return Some((target, state));
+        }
+    }
+    None
+}
+
+fn break_block(player: &Player, camera: &Camera, world: &mut World) -> Option<(Vec3i, BlockState)> {
+    let eye = player.position() + player.height_vector();
+    match world.raycast(&eye, camera.forward(), REACH) {
+        Some((hit, _)) if world.set_block(&hit, BlockState::air()) => Some((hit, BlockState::air())),
+        _ => None
+    }
+}
+
+fn draw_rebinding(player: &Player, writer: &mut Text, window: &Window) {
+    if let Some(rebinding) = player.controls().rebinding() {
+        let action = rebinding.action();
+        let top = window.size().1 as f32 / 2.0;
+        writer.render(&format!("Rebinding {:?}: {}", action, player.controls().describe(action)),
+                      10.0, top, 0.5, &vec3(1.0, 1.0, 1.0));
+        writer.render("Press an input to add it, Escape for next action, Shift+Escape to clear, Ctrl+Escape to finish",
+                      10.0, top - 24.0, 0.35, &vec3(0.8, 0.8, 0.8));
+    }
+}
+
+fn draw_frames(timer: &Timer, text: &mut Text, window: &Window) {
+    text.render(&format!("{}", timer.fps), 10.0, window.size().1 as f32 - 30.0, 0.5, &vec3(1.0, 1.0, 0.0));
+}
+
+fn draw_connection(client: &Client, text: &mut Text, window: &Window) {
+    let status = if client.is_connected() {
+        format!("{} ({} other players)", client.address(), client.players().len())
+    } else {
+        format!("Disconnected from {}", client.address())
+    };
+    text.render(&status, 10.0, window.size().1 as f32 - 54.0, 0.35, &vec3(0.8, 0.8, 0.8));
+}
+
+fn reload_resources(world: &mut World, renderer: &mut WorldRenderer, lighting: &mut Lighting, messages: &mut MessageLog, time: u64) {
+    shader::request_reload();
+    match block_database::reload() {
+        Ok(_) => {
+            renderer.reload_textures();
+            renderer.remesh(world);
+            lighting.reload_lights();
+            messages.push("Reloaded block definitions and shaders", vec3(0.6, 1.0, 0.6), time);
+        },
+        Err(e) => messages.push(&format!("Failed to reload blocks: {}", e), vec3(1.0, 0.4, 0.4), time)
+    }
+}
+
+fn take_screenshot(window: &Window, gl: &Gl, lighting: &Lighting, g_buffer: bool) {
+    let name = format!("screenshot_{}", capture::timestamp());
+    match capture_frame(window, gl, lighting, Path::new(SCREENSHOT_DIRECTORY), &name, g_buffer) {
+        Ok(_) => println!("Saved screenshot: {}/{}.png", SCREENSHOT_DIRECTORY, name),
+        Err(e) => println!("Failed to save screenshot: {}", e)
+    }
+}
+
+pub fn start(window: &mut Window, gl: &Gl, headless: Option<Headless>, mut settings: SettingsStore, input: InputSource,
+             remote: Option<Connect>) {
+    let mut client = match remote {
+        Some(ref remote) => match connect(remote) {
+            Ok(client) => Some(client),
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        },
+        None => None
+    };
+    let input = match input {
+        InputSource::Live => InputSource::Live,
+        _ if client.is_some() => {
+            println!("Recording and replay are not supported in multiplayer");
+            InputSource::Live
+        },
+        input => input
+    };
+    let (mut recorder, mut replay) = open_input_source(input, settings.get());
+    let (seed, control_settings) = match replay {
+        Some(ref r) => (r.header().seed, r.header().controls.clone()),
+        None => (settings.get().world.seed, settings.get().controls.clone())
+    };
+    noise::set_seed(seed);
 
-pub fn start(window: &mut Window, gl: &Gl) {
     let mut camera = Camera::new(vec3(0.0, 120.0, -2.0), vec3(0.0, 0.0, 1.0), window.framebuffer_size());
+    camera.set_fov(settings.get().video.fov, window.framebuffer_size());
+    camera.set_mouse_speed(control_settings.mouse_speed);
     let mut player = Player::new();
+    let mut controls = Controls::new(control_settings.bindings.clone());
+    controls.set_gamepad(Gamepad::new(control_settings.gamepad.clone()));
+    player.set_controls(controls);
+    let (mut show_debug, mut show_inventory) = (true, false);
+    let mut chat = Chat::new();
     let mut timer = Timer::new();
     let mut writer = Text::from_font(gl, "archivo.ttf", window.framebuffer_size());
-    let mut world = World::new(gl);
+    let mut messages = MessageLog::new();
+    let mut world = match client {
+        Some(_) => World::remote(settings.get().world.render_distance, settings.get().world.threads),
+        None => World::new(settings.get().world.render_distance, settings.get().world.threads)
+    };
+    let mut renderer = WorldRenderer::new(gl, settings.get().world.threads);
     let mut lighting = Lighting::new(gl, window.framebuffer_size());
+    let mut hdr = HdrBuffer::new(gl, window.framebuffer_size());
+    lighting.set_render_distance(settings.get().world.render_distance);
+    apply_video_settings(settings.get(), &mut hdr, &mut lighting);
     let skybox = SkyBox::new(gl);
+    lighting.set_fog_color(skybox.horizon_color());
     player.set_position(camera.position());
 
-    world.initialize_chunks();
+    if client.is_none() {
+        world.initialize_chunks();
+    }
 
     let shader = shader::Shader::new(gl, shader::Type::Block, false).unwrap();
 
-    lighting.add_light(BlockType::Sun, vec3(0.0, 256.0, 0.0));
+    if let Some(sun) = block_database::get().block_type(keys::SUN) {
+        lighting.add_light(sun, vec3(0.0, 256.0, 0.0));
+    }
 
     shader.bind();
 
     'load_spawn: loop {
+        if let Some(ref c) = client {
+            camera.set_position(c.spawn());
+            break 'load_spawn;
+        }
         if let Some(pos) = *world.get_player_spawn() {
             camera.set_position(pos);
             break 'load_spawn;
@@ -37,30 +336,205 @@ pub fn start(window: &mut Window, gl: &Gl) {
         }
     }
 
+    if let Some(ref h) = headless {
+        if replay.is_none() && client.is_none() {
+            if let Some(pos) = h.position {
+                camera.set_position(pos);
+            }
+            h.position_camera(&mut camera, 0);
+        }
+        player.set_position(camera.position());
+        let distance = match client {
+            Some(ref c) => c.view_distance().min(settings.get().world.render_distance),
+            None => settings.get().world.render_distance
+        }.min(HEADLESS_LOAD_DISTANCE);
+        wait_for_chunks(&mut world, &mut client, &player.position(), distance);
+        renderer.sync(&mut world, &player.position());
+        while renderer.is_meshing() {
+            renderer.sync(&mut world, &player.position());
+        }
+    }
+
+    if recorder.is_some() || replay.is_some() {
+        wait_for_chunks(&mut world, &mut client, &player.position(), 1);
+    }
+
+    let simulate = headless.is_none() || replay.is_some();
+    let mut pending_input = InputQueue::new();
+    let mut previous_position = *camera.position();
+    let mut screenshot = None;
+    let mut frame = 0;
     while window.is_open() {
-        timer.tick();
+        let ticks = timer.tick();
+        let ticks = if headless.is_some() { 1 } else { ticks };
         window.process_events(gl);
-        player.set_frame_leap(timer.frame_leap());
-        player.update(&mut camera, window.get_window());
+        pending_input.append(window.input());
+        renderer.animate_textures(&timer);
+        if let Some(ref mut c) = client {
+            handle_client_events(c, &mut world, &mut player, &mut camera, &mut previous_position, &mut messages, timer.elapsed());
+        }
+        match headless {
+            Some(ref h) if !simulate => h.position_camera(&mut camera, frame),
+            _ => {}
+        }
+
+        let mut stalled = false;
+        for _ in 0..(if simulate { ticks } else { 0 }) {
+            let replaying = replay.as_ref().map_or(false, |r| !r.is_finished());
+            if headless.is_some() && !replaying { break; }
+            if (replaying || recorder.is_some()) && !world.is_loaded_within(&player.position(), 1) {
+                stalled = true;
+                break;
+            }
+            previous_position = *camera.position();
+            let input = match replay {
+                Some(ref r) if replaying => r.next_frame().unwrap().clone(),
+                _ => player.controls_mut().capture(window.get_window(), &pending_input)
+            };
+            pending_input.clear();
+
+            if chat.is_open() {
+                if let Some(message) = chat.update(&input.events) {
+                    match client {
+                        Some(ref mut c) => c.send_chat(&message),
+                        None => messages.push(&format!("<Player> {}", message), vec3(1.0, 1.0, 1.0), timer.elapsed())
+                    }
+                }
+            } else if input.events.key_pressed(Key::Escape) && player.controls().rebinding().is_none() {
+                window.close();
+            }
+            player.controls_mut().set_suspended(chat.is_open());
+
+            let was_rebinding = player.controls().rebinding().is_some();
+            player.update(&mut camera, &input);
+            if was_rebinding && player.controls().rebinding().is_none() {
+                let bindings = player.controls().bindings().clone();
+                settings.update(|s| s.controls.bindings = bindings.clone());
+                messages.push("Saved control bindings", vec3(0.6, 1.0, 0.6), timer.elapsed());
+            }
+
+            if let Some(ref mut c) = client {
+                c.send_position(player.position(), camera.rotation().x, camera.rotation().y);
+            }
+
+            let clicked = |action: KeyAction| player.controls().key_state(action) == &KeyState::Clicked;
+            if adjust_post_processing(&player, &mut settings) {
+                apply_video_settings(settings.get(), &mut hdr, &mut lighting);
+            }
+            let mut changes = Vec::new();
+            if clicked(KeyAction::Use) {
+                changes.extend(place_block(&player, &camera, &mut world));
+            }
+            if clicked(KeyAction::Attack) {
+                changes.extend(break_block(&player, &camera, &mut world));
+            }
+            if let Some(ref mut c) = client {
+                for (position, state) in changes {
+                    if let Err(e) = c.send_block_change(position, state) {
+                        messages.push(&e, vec3(1.0, 0.4, 0.4), timer.elapsed());
+                        world.set_block(&position, BlockState::air());
+                    }
+                }
+            }
+            if clicked(KeyAction::Debug) {
+                show_debug = !show_debug;
+            }
+            if clicked(KeyAction::Inventory) {
+                show_inventory = !show_inventory;
+            }
+            if clicked(KeyAction::Chat) {
+                chat.open();
+            }
+            if clicked(KeyAction::Reload) {
+                reload_resources(&mut world, &mut renderer, &mut lighting, &mut messages, timer.elapsed());
+            }
+            if clicked(KeyAction::Screenshot) {
+                screenshot = Some(false);
+            }
+            if clicked(KeyAction::GBufferScreenshot) {
+                screenshot = Some(true);
+            }
+            if clicked(KeyAction::Rebind) {
+                player.controls_mut().begin_rebinding(&input);
+            }
+
+            let position = player.position();
+            let position = [position.x, position.y, position.z];
+            if let Some(e) = recorder.as_mut().and_then(|r| r.record(&RecordedFrame { input, position }).err()) {
+                println!("Stopped recording: {}", e);
+                recorder = None;
+            }
+            if let Some(ref mut r) = replay {
+                if replaying {
+                    r.advance(position);
+                    if r.is_finished() {
+                        println!("Replay finished after {} ticks at {:?}{}", r.len(), position,
+                                 if r.has_diverged() { " (diverged)" } else { "" });
+                    }
+                }
+            }
+        }
+        if simulate {
+            camera.interpolate(&previous_position, if headless.is_some() { 1.0 } else { timer.interpolation() });
+        }
         
-        world.render(&shader, &camera);
+        renderer.render(&world, &shader, &camera);
 
         lighting.bind_framebuffer();
 
         world.build_chunks(&player.position());
-        world.render(&shader, &camera);
+        renderer.render(&world, &shader, &camera);
         world.take_chunk_from_queue();
+        renderer.sync(&mut world, &player.position());
 
         lighting.unbind_framebuffer();
+        lighting.compute_occlusion(&camera);
+        hdr.bind_framebuffer();
         lighting.apply_lighting(&player.position());
-        lighting.copy_depth_buffer();
-        world.bind_block_texture(0);
+        lighting.copy_depth_buffer(hdr.framebuffer());
+        renderer.bind_block_texture(0);
         lighting.render_lighting(&camera);
 
         skybox.draw(&camera.view(), &camera.projection());
-        timer.draw_frames(&mut writer, &window);
-        player.draw_position(&mut writer);
+        hdr.unbind_framebuffer();
+        hdr.apply_bloom();
+        hdr.render();
+
+        if let Some(ref h) = headless {
+            if !stalled {
+                if let Err(e) = h.capture(window, gl, &lighting, frame) {
+                    println!("Failed to capture frame {}: {}", frame, e);
+                }
+                frame += 1;
+            }
+            if frame >= h.frames || replay.as_ref().map_or(false, |r| r.is_finished()) { break; }
+        } else {
+            if show_debug {
+                draw_frames(&timer, &mut writer, &window);
+                player.draw_position(&mut writer);
+                if let Some(ref c) = client {
+                    draw_connection(c, &mut writer, &window);
+                }
+            }
+            if show_inventory {
+                player.draw_inventory(&mut writer, &window);
+            }
+            draw_rebinding(&player, &mut writer, &window);
+            chat.draw(&mut writer);
+            for error in shader::take_reload_errors() {
+                messages.push(&error, vec3(1.0, 0.4, 0.4), timer.elapsed());
+            }
+            messages.draw(&mut writer, &window, timer.elapsed());
+
+            if let Some(g_buffer) = screenshot.take() {
+                take_screenshot(window, gl, &lighting, g_buffer);
+            }
+        }
 
         window.swap_buffers();
     }
+
+    if let Some(r) = recorder {
+        println!("Recorded {} ticks", r.frames());
+    }
 }
@@ -0,0 +1,196 @@
+use glfw::{Key, MouseButton, Action, Modifiers, Window};
+use serde::{Serialize, Serializer, Deserialize, Deserializer, de::Error};
+use std::fmt;
+
+pub const KEYS: [Key; 120] = [
+    Key::Space, Key::Apostrophe, Key::Comma, Key::Minus, Key::Period, Key::Slash,
+    Key::Num0, Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9,
+    Key::Semicolon, Key::Equal,
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J, Key::K, Key::L, Key::M,
+    Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+    Key::LeftBracket, Key::Backslash, Key::RightBracket, Key::GraveAccent, Key::World1, Key::World2,
+    Key::Escape, Key::Enter, Key::Tab, Key::Backspace, Key::Insert, Key::Delete,
+    Key::Right, Key::Left, Key::Down, Key::Up, Key::PageUp, Key::PageDown, Key::Home, Key::End,
+    Key::CapsLock, Key::ScrollLock, Key::NumLock, Key::PrintScreen, Key::Pause,
+    Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12,
+    Key::F13, Key::F14, Key::F15, Key::F16, Key::F17, Key::F18, Key::F19, Key::F20, Key::F21, Key::F22, Key::F23,
+    Key::F24, Key::F25,
+    Key::Kp0, Key::Kp1, Key::Kp2, Key::Kp3, Key::Kp4, Key::Kp5, Key::Kp6, Key::Kp7, Key::Kp8, Key::Kp9,
+    Key::KpDecimal, Key::KpDivide, Key::KpMultiply, Key::KpSubtract, Key::KpAdd, Key::KpEnter, Key::KpEqual,
+    Key::LeftShift, Key::LeftControl, Key::LeftAlt, Key::LeftSuper,
+    Key::RightShift, Key::RightControl, Key::RightAlt, Key::RightSuper, Key::Menu
+];
+
+pub const MOUSE_BUTTONS: [MouseButton; 8] = [
+    MouseButton::Button1, MouseButton::Button2, MouseButton::Button3, MouseButton::Button4,
+    MouseButton::Button5, MouseButton::Button6, MouseButton::Button7, MouseButton::Button8
+];
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone, Serialize, Deserialize)]
+pub enum Modifier {
+    Shift,
+    Control,
+    Alt,
+    Super
+}
+
+impl Modifier {
+    pub fn all() -> [Modifier; 4] {
+        [Modifier::Shift, Modifier::Control, Modifier::Alt, Modifier::Super]
+    }
+
+    pub fn keys(&self) -> [Key; 2] {
+        match *self {
+            Modifier::Shift => [Key::LeftShift, Key::RightShift],
+            Modifier::Control => [Key::LeftControl, Key::RightControl],
+            Modifier::Alt => [Key::LeftAlt, Key::RightAlt],
+            Modifier::Super => [Key::LeftSuper, Key::RightSuper]
+        }
+    }
+
+    pub fn for_key(key: Key) -> Option<Modifier> {
+        Modifier::all().iter().cloned().find(|m| m.keys().contains(&key))
+    }
+
+    pub fn is_held(&self, held: &[Input]) -> bool {
+        self.keys().iter().any(|&k| held.contains(&Input::Key(k)))
+    }
+
+    pub fn from_flags(flags: Modifiers) -> Vec<Modifier> {
+        let mut modifiers = Vec::new();
+        if flags.contains(Modifiers::Shift) { modifiers.push(Modifier::Shift); }
+        if flags.contains(Modifiers::Control) { modifiers.push(Modifier::Control); }
+        if flags.contains(Modifiers::Alt) { modifiers.push(Modifier::Alt); }
+        if flags.contains(Modifiers::Super) { modifiers.push(Modifier::Super); }
+        modifiers
+    }
+
+    fn parse(name: &str) -> Option<Modifier> {
+        Modifier::all().iter().cloned().find(|m| format!("{:?}", m).eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum Input {
+    Key(Key),
+    Mouse(MouseButton)
+}
+
+impl Input {
+    fn is_pressed(&self, window: &Window) -> bool {
+        match *self {
+            Input::Key(key) => window.get_key(key) == Action::Press,
+            Input::Mouse(button) => window.get_mouse_button(button) == Action::Press
+        }
+    }
+
+    pub fn pressed(window: &Window) -> Vec<Input> {
+        KEYS.iter().map(|&k| Input::Key(k))
+            .chain(MOUSE_BUTTONS.iter().map(|&b| Input::Mouse(b)))
+            .filter(|i| i.is_pressed(window))
+            .collect()
+    }
+
+    pub fn parse(name: &str) -> Option<Input> {
+        if name.len() > 5 && name[..5].eq_ignore_ascii_case("mouse") {
+            return name[5..].parse::<usize>().ok()
+                .and_then(|n| MOUSE_BUTTONS.get(n.wrapping_sub(1)))
+                .map(|&b| Input::Mouse(b));
+        }
+        KEYS.iter().find(|k| format!("{:?}", k).eq_ignore_ascii_case(name)).map(|&k| Input::Key(k))
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Input::Key(key) => write!(f, "{:?}", key),
+            Input::Mouse(button) => write!(f, "Mouse{}", button as i32 + 1)
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub struct Chord {
+    pub modifiers: Vec<Modifier>,
+    pub input: Input
+}
+
+impl Chord {
+    pub fn key(key: Key) -> Chord {
+        Chord { modifiers: Vec::new(), input: Input::Key(key) }
+    }
+
+    pub fn mouse(button: MouseButton) -> Chord {
+        Chord { modifiers: Vec::new(), input: Input::Mouse(button) }
+    }
+
+    pub fn with(mut self, modifier: Modifier) -> Chord {
+        if !self.modifiers.contains(&modifier) {
+            self.modifiers.push(modifier);
+            self.modifiers.sort();
+        }
+        self
+    }
+
+    pub fn is_held(&self, held: &[Input]) -> bool {
+        held.contains(&self.input) && self.modifiers.iter().all(|m| m.is_held(held))
+    }
+
+    pub fn matches(&self, input: Input, modifiers: &[Modifier]) -> bool {
+        self.input == input && self.modifiers.iter().all(|m| modifiers.contains(m))
+    }
+
+    pub fn extends(&self, other: &Chord) -> bool {
+        self.input == other.input && self.modifiers.len() > other.modifiers.len() &&
+            other.modifiers.iter().all(|m| self.modifiers.contains(m))
+    }
+
+    pub fn parse(text: &str) -> Result<Chord, String> {
+        let mut parts: Vec<&str> = text.split('+').map(|p| p.trim()).collect();
+        let input = parts.pop()
+            .and_then(Input::parse)
+            .ok_or(format!("Unknown input in binding: {}", text))?;
+        let mut chord = Chord { modifiers: Vec::new(), input };
+        for part in parts {
+            let modifier = Modifier::parse(part).ok_or(format!("Unknown modifier in binding: {}", text))?;
+            chord = chord.with(modifier);
+        }
+        Ok(chord)
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for modifier in &self.modifiers {
+            write!(f, "{:?}+", modifier)?;
+        }
+        write!(f, "{}", self.input)
+    }
+}
+
+impl Serialize for Input {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Input {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Input, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Input::parse(&text).ok_or(D::Error::custom(format!("Unknown input: {}", text)))
+    }
+}
+
+impl Serialize for Chord {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Chord {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Chord, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Chord::parse(&text).map_err(D::Error::custom)
+    }
+}
\ No newline at end of file
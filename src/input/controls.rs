@@ -0,0 +1,293 @@
+use glfw::{Key, MouseButton, Window};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use input::bindings::{Chord, Input, Modifier};
+use input::gamepad::{Gamepad, GamepadSettings};
+use input::events::{InputAction, InputEvent, InputFrame, InputQueue};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum KeyState {
+    Active,
+    Inactive,
+    Clicked,
+}
+
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum KeyAction {
+    Forward,
+    Back,
+    Left,
+    Right,
+    Jump,
+    Sprint,
+    Crouch,
+    Attack,
+    Use,
+    Inventory,
+    Chat,
+    Debug,
+    Hotbar1,
+    Hotbar2,
+    Hotbar3,
+    Hotbar4,
+    Hotbar5,
+    Hotbar6,
+    Hotbar7,
+    Hotbar8,
+    Hotbar9,
+    Screenshot,
+    GBufferScreenshot,
+    ExposureUp,
+    ExposureDown,
+    BloomUp,
+    BloomDown,
+    ToggleSsao,
+    SsaoQuality,
+    CycleBlock,
+    Reload,
+    Rebind,
+}
+
+pub const HOTBAR: [KeyAction; 9] = [
+    KeyAction::Hotbar1, KeyAction::Hotbar2, KeyAction::Hotbar3, KeyAction::Hotbar4, KeyAction::Hotbar5,
+    KeyAction::Hotbar6, KeyAction::Hotbar7, KeyAction::Hotbar8, KeyAction::Hotbar9
+];
+
+pub type Bindings = BTreeMap<KeyAction, Vec<Chord>>;
+
+pub fn default_bindings() -> Bindings {
+    use self::KeyAction::*;
+    let mut bindings: Bindings = vec![
+        (Forward, vec![Chord::key(Key::W), Chord::key(Key::Up)]),
+        (Back, vec![Chord::key(Key::S), Chord::key(Key::Down)]),
+        (Left, vec![Chord::key(Key::A), Chord::key(Key::Left)]),
+        (Right, vec![Chord::key(Key::D), Chord::key(Key::Right)]),
+        (Jump, vec![Chord::key(Key::Space)]),
+        (Sprint, vec![Chord::key(Key::LeftShift)]),
+        (Crouch, vec![Chord::key(Key::LeftControl)]),
+        (Attack, vec![Chord::mouse(MouseButton::Button1)]),
+        (Use, vec![Chord::mouse(MouseButton::Button2)]),
+        (Inventory, vec![Chord::key(Key::E)]),
+        (Chat, vec![Chord::key(Key::T), Chord::key(Key::Enter)]),
+        (Debug, vec![Chord::key(Key::F3)]),
+        (Screenshot, vec![Chord::key(Key::F2)]),
+        (GBufferScreenshot, vec![Chord::key(Key::F2).with(Modifier::Shift)]),
+        (ExposureUp, vec![Chord::key(Key::RightBracket)]),
+        (ExposureDown, vec![Chord::key(Key::LeftBracket)]),
+        (BloomUp, vec![Chord::key(Key::Period)]),
+        (BloomDown, vec![Chord::key(Key::Comma)]),
+        (ToggleSsao, vec![Chord::key(Key::F4)]),
+        (SsaoQuality, vec![Chord::key(Key::F5)]),
+        (CycleBlock, vec![Chord::key(Key::Q)]),
+        (Reload, vec![Chord::key(Key::F6)]),
+        (Rebind, vec![Chord::key(Key::F7)]),
+    ].into_iter().collect();
+
+    let digits = [Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9];
+    for (action, &key) in HOTBAR.iter().zip(digits.iter()) {
+        bindings.insert(*action, vec![Chord::key(key)]);
+    }
+    bindings
+}
+
+const REBIND_CONTROL: Key = Key::Escape;
+
+pub struct Rebinding {
+    actions: Vec<KeyAction>,
+    current: usize,
+    held: HashSet<Input>,
+    pending_modifier: Option<Key>
+}
+
+impl Rebinding {
+    pub fn action(&self) -> KeyAction {
+        self.actions[self.current]
+    }
+}
+
+pub struct Controls {
+    bindings: Bindings,
+    states: HashMap<KeyAction, KeyState>,
+    rebinding: Option<Rebinding>,
+    armed: HashSet<KeyAction>,
+    suspended: bool,
+    gamepad: Gamepad,
+    pub cursor_speed: f32
+}
+
+impl Controls {
+    pub fn default() -> Controls {
+        Controls::new(default_bindings())
+    }
+
+    pub fn new(bindings: Bindings) -> Controls {
+        let mut merged = default_bindings();
+        merged.extend(bindings);
+        Controls {
+            bindings: merged,
+            cursor_speed: 0.05,
+            states: HashMap::new(),
+            rebinding: None,
+            armed: HashSet::new(),
+            suspended: false,
+            gamepad: Gamepad::new(GamepadSettings { enabled: false, ..Default::default() })
+        }
+    }
+
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    pub fn set_gamepad(&mut self, gamepad: Gamepad) {
+        self.gamepad = gamepad;
+    }
+
+    pub fn gamepad(&self) -> &Gamepad {
+        &self.gamepad
+    }
+
+    pub fn set_suspended(&mut self, suspended: bool) {
+        self.suspended = suspended;
+    }
+
+    pub fn key_state(&self, action: KeyAction) -> &KeyState {
+        self.states.get(&action).unwrap_or(&KeyState::Inactive)
+    }
+
+    pub fn describe(&self, action: KeyAction) -> String {
+        match self.bindings.get(&action) {
+            Some(chords) if !chords.is_empty() => chords.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(", "),
+            _ => "unbound".to_string()
+        }
+    }
+
+    pub fn capture(&mut self, window: &Window, events: &InputQueue) -> InputFrame {
+        let gamepad = self.gamepad.poll(window.glfw);
+        InputFrame::capture(window, events, gamepad)
+    }
+
+    pub fn update(&mut self, frame: &InputFrame) {
+        self.gamepad.set_state(frame.gamepad.clone());
+        if self.rebinding.is_some() || self.suspended {
+            if self.rebinding.is_some() {
+                self.update_rebinding(frame);
+            }
+            self.states.clear();
+            self.armed.clear();
+            return;
+        }
+
+        let held: Vec<(KeyAction, &Chord)> = self.bindings.iter()
+            .flat_map(|(action, chords)| chords.iter().map(move |c| (*action, c)))
+            .filter(|&(_, chord)| chord.is_held(&frame.held))
+            .collect();
+        let mut active: HashSet<KeyAction> = held.iter()
+            .filter(|&&(_, chord)| !held.iter().any(|&(_, other)| other.extends(chord)))
+            .map(|&(action, _)| action)
+            .collect();
+        active.extend(self.bindings.keys().cloned().filter(|&action| self.gamepad.is_held(action)));
+
+        for event in frame.events.events() {
+            if let InputEvent::Button(pressed, InputAction::Press, ref modifiers) = *event {
+                let matching: Vec<(KeyAction, &Chord)> = self.bindings.iter()
+                    .flat_map(|(action, chords)| chords.iter().map(move |c| (*action, c)))
+                    .filter(|&(_, chord)| chord.matches(pressed, modifiers))
+                    .collect();
+                self.armed.extend(matching.iter()
+                    .filter(|&&(_, chord)| !matching.iter().any(|&(_, other)| other.extends(chord)))
+                    .map(|&(action, _)| action));
+            }
+        }
+
+        for action in self.bindings.keys() {
+            let previous = self.states.get(action).cloned().unwrap_or(KeyState::Inactive);
+            if previous == KeyState::Inactive && self.gamepad.is_held(*action) {
+                self.armed.insert(*action);
+            }
+            let state = if active.contains(action) {
+                KeyState::Active
+            } else if self.armed.remove(action) {
+                KeyState::Clicked
+            } else {
+                KeyState::Inactive
+            };
+            self.states.insert(*action, state);
+        }
+    }
+
+    pub fn begin_rebinding(&mut self, frame: &InputFrame) {
+        self.rebinding = Some(Rebinding {
+            actions: self.bindings.keys().cloned().collect(),
+            current: 0,
+            held: frame.held.iter().cloned().collect(),
+            pending_modifier: None
+        });
+        self.states.clear();
+    }
+
+    pub fn rebinding(&self) -> Option<&Rebinding> {
+        self.rebinding.as_ref()
+    }
+
+    fn update_rebinding(&mut self, frame: &InputFrame) {
+        let pressed: HashSet<Input> = frame.held.iter().cloned().collect();
+        let (action, previous) = {
+            let rebinding = self.rebinding.as_mut().unwrap();
+            let previous = ::std::mem::replace(&mut rebinding.held, pressed.clone());
+            (rebinding.action(), previous)
+        };
+        let modifiers: Vec<Modifier> = Modifier::all().iter().cloned().filter(|m| m.is_held(&frame.held)).collect();
+
+        for &input in pressed.difference(&previous) {
+            match input {
+                Input::Key(REBIND_CONTROL) => {
+                    self.rebinding.as_mut().unwrap().pending_modifier = None;
+                    if modifiers.contains(&Modifier::Control) {
+                        self.rebinding = None;
+                        return;
+                    } else if modifiers.contains(&Modifier::Shift) {
+                        self.bindings.insert(action, Vec::new());
+                    } else {
+                        return self.next_rebind_action();
+                    }
+                },
+                Input::Key(key) if Modifier::for_key(key).is_some() => {
+                    self.rebinding.as_mut().unwrap().pending_modifier = Some(key);
+                },
+                _ => {
+                    let chord = modifiers.iter().fold(Chord { modifiers: Vec::new(), input }, |c, &m| c.with(m));
+                    self.rebinding.as_mut().unwrap().pending_modifier = None;
+                    self.add_binding(action, chord);
+                }
+            }
+        }
+
+        for &input in previous.difference(&pressed) {
+            let pending = self.rebinding.as_ref().unwrap().pending_modifier;
+            if let (Input::Key(key), Some(modifier)) = (input, pending) {
+                if key == modifier {
+                    self.rebinding.as_mut().unwrap().pending_modifier = None;
+                    self.add_binding(action, Chord::key(key));
+                }
+            }
+        }
+    }
+
+    fn add_binding(&mut self, action: KeyAction, chord: Chord) {
+        let chords = self.bindings.entry(action).or_insert(Vec::new());
+        if !chords.contains(&chord) {
+            chords.push(chord);
+        }
+    }
+
+    fn next_rebind_action(&mut self) {
+        let finished = {
+            let rebinding = self.rebinding.as_mut().unwrap();
+            rebinding.current += 1;
+            rebinding.pending_modifier = None;
+            rebinding.current >= rebinding.actions.len()
+        };
+        if finished {
+            self.rebinding = None;
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,103 @@
+use glfw::{self, Key, WindowEvent};
+use input::bindings::{Input, Modifier, KEYS};
+use input::gamepad::GamepadState;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum InputAction {
+    Press,
+    Release,
+    Repeat
+}
+
+impl InputAction {
+    fn from_glfw(action: glfw::Action) -> InputAction {
+        match action {
+            glfw::Action::Press => InputAction::Press,
+            glfw::Action::Release => InputAction::Release,
+            glfw::Action::Repeat => InputAction::Repeat
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum InputEvent {
+    Button(Input, InputAction, Vec<Modifier>),
+    Scroll(f64, f64),
+    Char(char)
+}
+
+impl InputEvent {
+    pub fn from_window_event(event: &WindowEvent) -> Option<InputEvent> {
+        match *event {
+            WindowEvent::Key(key, _, action, modifiers) if KEYS.contains(&key) =>
+                Some(InputEvent::Button(Input::Key(key), InputAction::from_glfw(action), Modifier::from_flags(modifiers))),
+            WindowEvent::MouseButton(button, action, modifiers) =>
+                Some(InputEvent::Button(Input::Mouse(button), InputAction::from_glfw(action), Modifier::from_flags(modifiers))),
+            WindowEvent::Scroll(x, y) => Some(InputEvent::Scroll(x, y)),
+            WindowEvent::Char(c) => Some(InputEvent::Char(c)),
+            _ => None
+        }
+    }
+
+    pub fn is_key(&self, key: Key, action: InputAction) -> bool {
+        match *self {
+            InputEvent::Button(Input::Key(k), a, _) => k == key && a == action,
+            _ => false
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct InputQueue {
+    events: Vec<InputEvent>
+}
+
+impl InputQueue {
+    pub fn new() -> InputQueue { Default::default() }
+
+    pub fn push(&mut self, event: InputEvent) {
+        self.events.push(event);
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn append(&mut self, other: &InputQueue) {
+        self.events.extend(other.events.iter().cloned());
+    }
+
+    pub fn events(&self) -> &[InputEvent] {
+        &self.events
+    }
+
+    pub fn key_pressed(&self, key: Key) -> bool {
+        self.events.iter().any(|e| e.is_key(key, InputAction::Press))
+    }
+
+    pub fn scroll(&self) -> (f64, f64) {
+        self.events.iter().fold((0.0, 0.0), |(x, y), e| match *e {
+            InputEvent::Scroll(dx, dy) => (x + dx, y + dy),
+            _ => (x, y)
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct InputFrame {
+    pub cursor: (f64, f64),
+    pub held: Vec<Input>,
+    pub gamepad: GamepadState,
+    pub events: InputQueue
+}
+
+impl InputFrame {
+    pub fn capture(window: &glfw::Window, events: &InputQueue, gamepad: GamepadState) -> InputFrame {
+        InputFrame {
+            cursor: window.get_cursor_pos(),
+            held: Input::pressed(window),
+            gamepad,
+            events: events.clone()
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,137 @@
+use glfw::{Glfw, JoystickId};
+use math::{vec2, Vec2};
+use std::collections::BTreeMap;
+use input::controls::KeyAction;
+
+const JOYSTICKS: [JoystickId; 4] = [
+    JoystickId::Joystick1, JoystickId::Joystick2, JoystickId::Joystick3, JoystickId::Joystick4
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GamepadSettings {
+    pub enabled: bool,
+    pub deadzone: f32,
+    pub curve: f32,
+    pub look_speed: f32,
+    pub invert_look: bool,
+    pub move_axes: (usize, usize),
+    pub look_axes: (usize, usize),
+    pub trigger_threshold: f32,
+    pub buttons: BTreeMap<KeyAction, Vec<usize>>,
+    pub triggers: BTreeMap<KeyAction, usize>
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        let mut buttons = BTreeMap::new();
+        buttons.insert(KeyAction::Jump, vec![0]);
+        buttons.insert(KeyAction::Crouch, vec![1, 9]);
+        buttons.insert(KeyAction::Inventory, vec![3]);
+        buttons.insert(KeyAction::CycleBlock, vec![5]);
+        buttons.insert(KeyAction::Chat, vec![6]);
+        buttons.insert(KeyAction::Debug, vec![7]);
+        buttons.insert(KeyAction::Sprint, vec![8]);
+
+        let mut triggers = BTreeMap::new();
+        triggers.insert(KeyAction::Use, 4);
+        triggers.insert(KeyAction::Attack, 5);
+
+        GamepadSettings {
+            enabled: true,
+            deadzone: 0.2,
+            curve: 2.0,
+            look_speed: 180.0,
+            invert_look: false,
+            move_axes: (0, 1),
+            look_axes: (2, 3),
+            trigger_threshold: 0.5,
+            buttons,
+            triggers
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GamepadState {
+    pub axes: Vec<f32>,
+    pub buttons: Vec<bool>
+}
+
+pub struct Gamepad {
+    settings: GamepadSettings,
+    joystick: Option<JoystickId>,
+    state: GamepadState
+}
+
+impl Gamepad {
+    pub fn new(settings: GamepadSettings) -> Gamepad {
+        Gamepad { settings, joystick: None, state: GamepadState::default() }
+    }
+
+    pub fn poll(&mut self, glfw: Glfw) -> GamepadState {
+        if !self.settings.enabled {
+            return GamepadState::default();
+        }
+        if let Some(id) = self.joystick {
+            if !glfw.get_joystick(id).is_present() {
+                println!("Gamepad disconnected");
+                self.joystick = None;
+            }
+        }
+        if self.joystick.is_none() {
+            self.joystick = JOYSTICKS.iter().cloned().find(|&id| glfw.get_joystick(id).is_present());
+            if let Some(id) = self.joystick {
+                println!("Gamepad connected: {}", glfw.get_joystick(id).get_name());
+            }
+        }
+        match self.joystick {
+            Some(id) => {
+                let joystick = glfw.get_joystick(id);
+                GamepadState {
+                    axes: joystick.get_axes(),
+                    buttons: joystick.get_buttons().iter().map(|&b| b != 0).collect()
+                }
+            },
+            None => GamepadState::default()
+        }
+    }
+
+    pub fn set_state(&mut self, state: GamepadState) {
+        self.state = state;
+    }
+
+    pub fn is_held(&self, action: KeyAction) -> bool {
+        let button = self.settings.buttons.get(&action)
+            .map_or(false, |buttons| buttons.iter().any(|&b| self.state.buttons.get(b).cloned().unwrap_or(false)));
+        let trigger = self.settings.triggers.get(&action)
+            .map_or(false, |&axis| self.axis(axis) > self.settings.trigger_threshold);
+        button || trigger
+    }
+
+    pub fn movement(&self) -> Vec2 {
+        let stick = self.stick(self.settings.move_axes);
+        vec2(stick.x, -stick.y)
+    }
+
+    pub fn look(&self) -> Vec2 {
+        let stick = self.stick(self.settings.look_axes) * self.settings.look_speed;
+        vec2(stick.x, if self.settings.invert_look { stick.y } else { -stick.y })
+    }
+
+    fn axis(&self, index: usize) -> f32 {
+        self.state.axes.get(index).cloned().unwrap_or(0.0)
+    }
+
+    fn stick(&self, axes: (usize, usize)) -> Vec2 {
+        let (x, y) = (self.axis(axes.0), self.axis(axes.1));
+        let magnitude = (x * x + y * y).sqrt();
+        let deadzone = self.settings.deadzone;
+        if magnitude <= deadzone || magnitude == 0.0 {
+            return vec2(0.0, 0.0);
+        }
+        let scaled = ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0);
+        let response = scaled.powf(self.settings.curve) / magnitude;
+        vec2(x * response, y * response)
+    }
+}
\ No newline at end of file
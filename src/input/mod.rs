@@ -0,0 +1,5 @@
+pub mod bindings;
+pub mod controls;
+pub mod gamepad;
+pub mod events;
+pub mod recording;
\ No newline at end of file
@@ -0,0 +1,118 @@
+use serde_json;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use settings::ControlSettings;
+use input::events::InputFrame;
+use util::timer::TICK_RATE;
+
+pub const RECORDING_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingHeader {
+    pub version: u32,
+    pub tick_rate: u32,
+    pub seed: u32,
+    pub controls: ControlSettings
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub input: InputFrame,
+    pub position: [f32; 3]
+}
+
+pub struct Recorder {
+    writer: BufWriter<File>,
+    frames: usize
+}
+
+impl Recorder {
+    pub fn create(path: &Path, header: &RecordingHeader) -> Result<Recorder, String> {
+        let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let mut recorder = Recorder { writer: BufWriter::new(file), frames: 0 };
+        recorder.write_line(header)?;
+        Ok(recorder)
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn record(&mut self, frame: &RecordedFrame) -> Result<(), String> {
+        self.write_line(frame)?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    fn write_line<T: ::serde::Serialize>(&mut self, value: &T) -> Result<(), String> {
+        serde_json::to_writer(&mut self.writer, value).map_err(|e| e.to_string())?;
+        self.writer.write_all(b"\n").and_then(|_| self.writer.flush()).map_err(|e| e.to_string())
+    }
+}
+
+pub struct Replay {
+    header: RecordingHeader,
+    frames: Vec<RecordedFrame>,
+    current: usize,
+    diverged: bool
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Replay, String> {
+        let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let mut lines = BufReader::new(file).lines();
+        let parse_error = |line: usize, e: String| format!("{}:{}: {}", path.display(), line, e);
+
+        let header: RecordingHeader = match lines.next() {
+            Some(line) => serde_json::from_str(&line.map_err(|e| parse_error(1, e.to_string()))?)
+                .map_err(|e| parse_error(1, e.to_string()))?,
+            None => return Err(format!("{} is empty", path.display()))
+        };
+        if header.version != RECORDING_VERSION {
+            return Err(format!("{} has recording version {}, expected {}", path.display(), header.version, RECORDING_VERSION));
+        }
+        if header.tick_rate != TICK_RATE {
+            return Err(format!("{} was recorded at {} ticks per second, expected {}", path.display(), header.tick_rate, TICK_RATE));
+        }
+
+        let mut frames = Vec::new();
+        for (i, line) in lines.enumerate() {
+            let line = line.map_err(|e| parse_error(i + 2, e.to_string()))?;
+            if line.trim().is_empty() { continue; }
+            frames.push(serde_json::from_str(&line).map_err(|e| parse_error(i + 2, e.to_string()))?);
+        }
+        Ok(Replay { header, frames, current: 0, diverged: false })
+    }
+
+    pub fn header(&self) -> &RecordingHeader {
+        &self.header
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current >= self.frames.len()
+    }
+
+    pub fn next_frame(&self) -> Option<&InputFrame> {
+        self.frames.get(self.current).map(|f| &f.input)
+    }
+
+    pub fn advance(&mut self, position: [f32; 3]) {
+        if let Some(frame) = self.frames.get(self.current) {
+            let distance = frame.position.iter().zip(position.iter()).map(|(a, b)| (a - b).abs()).fold(0.0, f32::max);
+            if distance > 0.001 && !self.diverged {
+                println!("Replay diverged at frame {}: recorded {:?}, replayed {:?}", self.current, frame.position, position);
+                self.diverged = true;
+            }
+        }
+        self.current += 1;
+    }
+
+    pub fn has_diverged(&self) -> bool {
+        self.diverged
+    }
+}
\ No newline at end of file
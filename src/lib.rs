@@ -0,0 +1,20 @@
+extern crate glm;
+#[macro_use] extern crate lazy_static;
+extern crate rand;
+extern crate noise;
+extern crate num_traits;
+extern crate serde;
+extern crate serde_json;
+#[macro_use] extern crate serde_derive;
+extern crate threadpool;
+extern crate png;
+extern crate zip;
+extern crate flate2;
+extern crate libc;
+
+pub mod util;
+pub mod world;
+pub mod server;
+pub mod net;
+
+use util::math;
\ No newline at end of file
@@ -1,3 +1,4 @@
+extern crate minecraft;
 extern crate gl as GL;
 extern crate freetype;
 extern crate glfw;
@@ -6,57 +7,119 @@ extern crate glm;
 extern crate stb_image;
 extern crate libc;
 extern crate rand;
-extern crate noise;
-extern crate num_traits;
 extern crate serde;
 extern crate serde_json;
 #[macro_use] extern crate serde_derive;
+extern crate png;
 extern crate threadpool;
 
+#[macro_use] mod macros;
 mod game;
 mod window;
 mod shader;
-#[macro_use] mod util;
 mod camera;
-mod world;
 mod player;
 mod render;
+mod settings;
+mod input;
+mod ui;
 
+use minecraft::{util, world};
 use std::env;
+use std::path::{Path, PathBuf};
 use util::math;
 
 struct VarArgs {
-    width: u32,
-    height: u32
+    headless: game::Headless,
+    input: game::InputSource,
+    connect: Option<String>,
+    name: String,
+    overrides: Vec<(String, String)>
+}
+
+fn parse_floats(val: &str) -> Option<Vec<f32>> {
+    val.split(",").map(|v| v.trim().parse::<f32>().ok()).collect()
 }
 
 fn main() {
     let args: Vec<String> = env::args().skip(1).collect();
 
-    let mut var_args: VarArgs = VarArgs { width: 0, height: 0 };
+    let mut var_args: VarArgs = VarArgs {
+        headless: game::Headless::new(0),
+        input: game::InputSource::Live,
+        connect: None,
+        name: "Player".to_string(),
+        overrides: Vec::new()
+    };
 
     for arg in args.iter() {
-        let key_val: Vec<&str> = arg.split("=").collect();
-        let key = key_val[0];
-        let val = key_val[1];
+        let (key, val) = match arg.find('=') {
+            Some(i) => (&arg[..i], &arg[i + 1..]),
+            None => {
+                println!("Ignoring argument without a value: {}. Expected key=value", arg);
+                continue;
+            }
+        };
 
-        if key.eq_ignore_ascii_case("width") {
-            var_args.width = val.parse::<u32>()
+        if key.eq_ignore_ascii_case("headless") {
+            var_args.headless.frames = val.parse::<u32>()
                 .unwrap_or_else(|_| {
-                    println!("Invalid screen width set. Defaulting to 800");
-                    800
+                    println!("Invalid headless frame count set. Defaulting to 1");
+                    1
                 });
-        }
-        if key.eq_ignore_ascii_case("height") {
-            var_args.height = val.parse::<u32>()
+        } else if key.eq_ignore_ascii_case("output") {
+            var_args.headless.output = PathBuf::from(val);
+        } else if key.eq_ignore_ascii_case("camera") {
+            match parse_floats(val) {
+                Some(ref p) if p.len() == 3 => var_args.headless.position = Some(math::vec3(p[0], p[1], p[2])),
+                _ => println!("Invalid camera position set. Expected camera=x,y,z")
+            }
+        } else if key.eq_ignore_ascii_case("look") {
+            match parse_floats(val) {
+                Some(ref r) if r.len() == 2 => {
+                    var_args.headless.yaw = r[0];
+                    var_args.headless.pitch = r[1];
+                },
+                _ => println!("Invalid camera rotation set. Expected look=yaw,pitch")
+            }
+        } else if key.eq_ignore_ascii_case("turn") {
+            var_args.headless.yaw_step = val.parse::<f32>()
                 .unwrap_or_else(|_| {
-                    println!("Invalid screen height set. Defaulting to 600");
-                    600
+                    println!("Invalid turn rate set. Defaulting to 0");
+                    0.0
                 });
+        } else if key.eq_ignore_ascii_case("gbuffer") {
+            var_args.headless.g_buffer = val.eq_ignore_ascii_case("true");
+        } else if key.eq_ignore_ascii_case("record") {
+            var_args.input = game::InputSource::Record(PathBuf::from(val));
+        } else if key.eq_ignore_ascii_case("replay") {
+            var_args.input = game::InputSource::Replay(PathBuf::from(val));
+        } else if key.eq_ignore_ascii_case("connect") {
+            var_args.connect = Some(val.to_string());
+        } else if key.eq_ignore_ascii_case("name") {
+            var_args.name = val.to_string();
+        } else {
+            var_args.overrides.push((key.to_string(), val.to_string()));
         }
     }
 
-    let mut window: window::Window = window::Window::new(var_args.width, var_args.height);
+    let mut settings = settings::SettingsStore::load(Path::new(settings::SETTINGS_FILE));
+    for key in settings.apply_overrides(&var_args.overrides) {
+        println!("Unknown argument: {}", key);
+    }
+    util::resources::set_packs(settings.get().video.resource_packs.clone());
+
+    let headless = match var_args.headless.frames {
+        0 => None,
+        _ => Some(var_args.headless)
+    };
+    let (width, height) = (settings.get().video.width, settings.get().video.height);
+    let mut window: window::Window = match headless {
+        Some(_) => window::Window::headless(width, height),
+        None => window::Window::new(width, height)
+    };
     let gl = window.gl_context();
-    game::start(&mut window, &gl);
+    let name = var_args.name;
+    let connect = var_args.connect.map(|address| game::Connect { address, name });
+    game::start(&mut window, &gl, headless, settings, var_args.input, connect);
 }
@@ -0,0 +1,178 @@
+use math::{Vec3, Vec3i};
+use net::connection::Connection;
+use net::protocol::{Packet, MAX_CHAT_LENGTH, PROTOCOL_VERSION};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use world::block::block_database;
+use world::block::block_id_map::BlockIdMap;
+use world::block::block_state::BlockState;
+use world::chunk::chunk_codec;
+use world::world::World;
+
+const LOGIN_TIMEOUT: u64 = 10;
+
+pub enum ClientEvent {
+    Chat(String),
+    Correction(Vec3),
+    Disconnected(String)
+}
+
+#[derive(Debug, Clone)]
+pub struct RemotePlayer {
+    pub position: Vec3,
+    pub yaw: f32,
+    pub pitch: f32
+}
+
+pub struct Client {
+    connection: Connection,
+    player_id: u32,
+    spawn: Vec3,
+    view_distance: i32,
+    players: HashMap<u32, RemotePlayer>,
+    ids: BlockIdMap,
+    backlog: Vec<Packet>,
+    connected: bool,
+    failure: Option<String>
+}
+
+impl Client {
+    pub fn connect(address: &str, name: &str) -> Result<Client, String> {
+        let mut connection = Connection::connect(address)?;
+        connection.send(&Packet::Handshake { version: PROTOCOL_VERSION })?;
+        connection.send(&Packet::Login { name: name.to_string() })?;
+
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(LOGIN_TIMEOUT) {
+            connection.flush()?;
+            let mut packets = connection.receive()?.into_iter();
+            while let Some(packet) = packets.next() {
+                match packet {
+                    Packet::LoginSuccess { player_id, position, view_distance } => {
+                        return Ok(Client {
+                            connection,
+                            player_id,
+                            spawn: position,
+                            view_distance,
+                            players: HashMap::new(),
+                            ids: BlockIdMap::new(),
+                            backlog: packets.collect(),
+                            connected: true,
+                            failure: None
+                        });
+                    },
+                    Packet::Disconnect { reason } => return Err(format!("Server refused login: {}", reason)),
+                    packet => return Err(format!("Unexpected packet during login: {:?}", packet))
+                }
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        Err(format!("Timed out logging in to {}", address))
+    }
+
+    pub fn address(&self) -> &str {
+        self.connection.peer()
+    }
+
+    pub fn spawn(&self) -> Vec3 {
+        self.spawn
+    }
+
+    pub fn view_distance(&self) -> i32 {
+        self.view_distance
+    }
+
+    pub fn players(&self) -> &HashMap<u32, RemotePlayer> {
+        &self.players
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    pub fn poll(&mut self, world: &mut World) -> Vec<ClientEvent> {
+        let mut events = Vec::new();
+        if !self.connected { return events; }
+        if let Some(e) = self.failure.take() {
+            self.disconnect(&mut events, e);
+            return events;
+        }
+
+        let mut packets: Vec<Packet> = self.backlog.drain(..).collect();
+        match self.connection.flush().and_then(|_| self.connection.receive()) {
+            Ok(received) => packets.extend(received),
+            Err(e) => {
+                self.disconnect(&mut events, e);
+                return events;
+            }
+        }
+        for packet in packets {
+            match packet {
+                Packet::BlockIds { keys } => self.ids = BlockIdMap::from_keys(keys),
+                Packet::ChunkData { x, z, data } => match chunk_codec::decode(&data, &self.ids) {
+                    Ok(blocks) => world.insert_chunk((x, z), Arc::new(blocks)),
+                    Err(e) => {
+                        self.disconnect(&mut events, format!("Invalid chunk {:?} from server: {}", (x, z), e));
+                        break;
+                    }
+                },
+                Packet::UnloadChunk { x, z } => world.remove_chunk(&(x, z)),
+                Packet::BlockChange { position, id, properties } => match self.ids.state(id, properties, &block_database::get()) {
+                    Ok(state) => { world.set_block(&position, state); },
+                    Err(e) => {
+                        self.disconnect(&mut events, format!("Invalid block change at {:?} from server: {}", position, e));
+                        break;
+                    }
+                },
+                Packet::PlayerPosition { player_id, position, .. } if player_id == self.player_id => {
+                    events.push(ClientEvent::Correction(position));
+                },
+                Packet::PlayerPosition { player_id, position, yaw, pitch } => {
+                    self.players.insert(player_id, RemotePlayer { position, yaw, pitch });
+                },
+                Packet::PlayerLeft { player_id } => { self.players.remove(&player_id); },
+                Packet::Chat { message } => events.push(ClientEvent::Chat(message)),
+                Packet::Disconnect { reason } => {
+                    self.disconnect(&mut events, format!("Disconnected: {}", reason));
+                    break;
+                },
+                packet => println!("Ignoring unexpected packet from server: {:?}", packet)
+            }
+        }
+        events
+    }
+
+    pub fn send_position(&mut self, position: Vec3, yaw: f32, pitch: f32) {
+        let player_id = self.player_id;
+        self.send(Packet::PlayerPosition { player_id, position, yaw, pitch });
+    }
+
+    pub fn send_block_change(&mut self, position: Vec3i, state: BlockState) -> Result<(), String> {
+        let database = block_database::get();
+        let id = self.ids.id(state.block_type, &database)
+            .ok_or_else(|| format!("{} is not known to the server", database.key(state.block_type)))?;
+        self.send(Packet::BlockChange { position, id, properties: state.properties() });
+        Ok(())
+    }
+
+    pub fn send_chat(&mut self, message: &str) {
+        let message: String = message.trim().chars().take(MAX_CHAT_LENGTH).collect();
+        if !message.is_empty() {
+            self.send(Packet::Chat { message });
+        }
+    }
+
+    fn send(&mut self, packet: Packet) {
+        if !self.connected || self.failure.is_some() { return; }
+        if let Err(e) = self.connection.send(&packet) {
+            self.failure = Some(e);
+        }
+    }
+
+    fn disconnect(&mut self, events: &mut Vec<ClientEvent>, reason: String) {
+        self.connected = false;
+        events.push(ClientEvent::Disconnected(reason));
+    }
+}
\ No newline at end of file
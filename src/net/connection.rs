@@ -0,0 +1,98 @@
+use net::protocol::{Packet, MAX_PACKET_SIZE};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+const MAX_BUFFERED: usize = 1 << 25;
+const CONNECT_TIMEOUT: u64 = 5;
+
+pub struct Connection {
+    stream: TcpStream,
+    peer: String,
+    incoming: Vec<u8>,
+    outgoing: Vec<u8>,
+    closed: bool
+}
+
+impl Connection {
+    pub fn new(stream: TcpStream) -> Result<Connection, String> {
+        let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or("unknown".to_string());
+        stream.set_nonblocking(true).map_err(|e| format!("Failed to configure connection to {}: {}", peer, e))?;
+        stream.set_nodelay(true).map_err(|e| format!("Failed to configure connection to {}: {}", peer, e))?;
+        Ok(Connection { stream, peer, incoming: Vec::new(), outgoing: Vec::new(), closed: false })
+    }
+
+    pub fn connect(address: &str) -> Result<Connection, String> {
+        let addresses = address.to_socket_addrs().map_err(|e| format!("Invalid server address {}: {}", address, e))?;
+        let mut error = format!("No addresses found for {}", address);
+        for address in addresses {
+            match TcpStream::connect_timeout(&address, Duration::from_secs(CONNECT_TIMEOUT)) {
+                Ok(stream) => return Connection::new(stream),
+                Err(e) => error = format!("Failed to connect to {}: {}", address, e)
+            }
+        }
+        Err(error)
+    }
+
+    pub fn peer(&self) -> &str {
+        &self.peer
+    }
+
+    pub fn send(&mut self, packet: &Packet) -> Result<(), String> {
+        self.outgoing.extend_from_slice(&packet.encode()?);
+        if self.outgoing.len() > MAX_BUFFERED {
+            self.closed = true;
+            return Err(format!("{} is not keeping up with sent data", self.peer));
+        }
+        self.flush()
+    }
+
+    pub fn flush(&mut self) -> Result<(), String> {
+        while !self.outgoing.is_empty() && !self.closed {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => self.closed = true,
+                Ok(count) => { self.outgoing.drain(..count); },
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.closed = true;
+                    return Err(format!("Failed to send to {}: {}", self.peer, e));
+                }
+            }
+        }
+        if self.closed { Err(format!("Connection to {} closed", self.peer)) } else { Ok(()) }
+    }
+
+    pub fn receive(&mut self) -> Result<Vec<Packet>, String> {
+        let mut buffer = [0; 16384];
+        while !self.closed {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => self.closed = true,
+                Ok(count) => self.incoming.extend_from_slice(&buffer[..count]),
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(format!("Failed to receive from {}: {}", self.peer, e))
+            }
+        }
+
+        let mut packets = Vec::new();
+        let mut start = 0;
+        while self.incoming.len() - start >= 4 {
+            let header = &self.incoming[start..start + 4];
+            let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+            if length == 0 || length > MAX_PACKET_SIZE {
+                return Err(format!("Invalid packet length {} from {}", length, self.peer));
+            }
+            if self.incoming.len() - start - 4 < length { break; }
+            packets.push(Packet::decode(&self.incoming[start + 4..start + 4 + length])
+                .map_err(|e| format!("Malformed packet from {}: {}", self.peer, e))?);
+            start += 4 + length;
+        }
+        self.incoming.drain(..start);
+
+        if packets.is_empty() && self.closed {
+            return Err(format!("Connection to {} closed", self.peer));
+        }
+        Ok(packets)
+    }
+}
\ No newline at end of file
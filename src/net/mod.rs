@@ -0,0 +1,3 @@
+pub mod protocol;
+pub mod connection;
+pub mod client;
\ No newline at end of file
@@ -0,0 +1,233 @@
+use math::{Vec3, Vec3i};
+
+pub const PROTOCOL_VERSION: u16 = 4;
+pub const MAX_PACKET_SIZE: usize = 1 << 22;
+pub const DEFAULT_PORT: u16 = 25565;
+pub const MAX_CHAT_LENGTH: usize = 256;
+
+#[derive(Debug, Clone)]
+pub enum Packet {
+    Handshake { version: u16 },
+    Login { name: String },
+    LoginSuccess { player_id: u32, position: Vec3, view_distance: i32 },
+    Disconnect { reason: String },
+    BlockIds { keys: Vec<String> },
+    ChunkData { x: i32, z: i32, data: Vec<u8> },
+    UnloadChunk { x: i32, z: i32 },
+    BlockChange { position: Vec3i, id: u16, properties: [u8; 3] },
+    PlayerPosition { player_id: u32, position: Vec3, yaw: f32, pitch: f32 },
+    PlayerLeft { player_id: u32 },
+    Chat { message: String }
+}
+
+impl Packet {
+    pub fn encode(&self) -> Result<Vec<u8>, String> {
+        let mut w = PacketWriter { data: vec![0; 4] };
+        match *self {
+            Packet::Handshake { version } => {
+                w.u8(0);
+                w.u16(version);
+            },
+            Packet::Login { ref name } => {
+                w.u8(1);
+                w.string(name)?;
+            },
+            Packet::LoginSuccess { player_id, ref position, view_distance } => {
+                w.u8(2);
+                w.u32(player_id);
+                w.vec3(position);
+                w.i32(view_distance);
+            },
+            Packet::Disconnect { ref reason } => {
+                w.u8(3);
+                w.string(reason)?;
+            },
+            Packet::ChunkData { x, z, ref data } => {
+                w.u8(4);
+                w.i32(x);
+                w.i32(z);
+                w.u32(data.len() as u32);
+                w.data.extend_from_slice(data);
+            },
+            Packet::UnloadChunk { x, z } => {
+                w.u8(5);
+                w.i32(x);
+                w.i32(z);
+            },
+            Packet::BlockChange { ref position, id, ref properties } => {
+                w.u8(6);
+                w.i32(position.x);
+                w.i32(position.y);
+                w.i32(position.z);
+                w.u16(id);
+                w.data.extend_from_slice(properties);
+            },
+            Packet::PlayerPosition { player_id, ref position, yaw, pitch } => {
+                w.u8(7);
+                w.u32(player_id);
+                w.vec3(position);
+                w.f32(yaw);
+                w.f32(pitch);
+            },
+            Packet::PlayerLeft { player_id } => {
+                w.u8(8);
+                w.u32(player_id);
+            },
+            Packet::Chat { ref message } => {
+                w.u8(9);
+                w.string(message)?;
+            },
+            Packet::BlockIds { ref keys } => {
+                w.u8(10);
+                if keys.len() > u16::max_value() as usize {
+                    return Err(format!("Too many block ids to send: {}", keys.len()));
+                }
+                w.u16(keys.len() as u16);
+                for key in keys {
+                    w.string(key)?;
+                }
+            }
+        }
+        let length = w.data.len() - 4;
+        if length > MAX_PACKET_SIZE {
+            return Err(format!("Packet of {} bytes exceeds the {} byte limit", length, MAX_PACKET_SIZE));
+        }
+        w.data[..4].copy_from_slice(&(length as u32).to_be_bytes());
+        Ok(w.data)
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Packet, String> {
+        let mut r = PacketReader { data, position: 0 };
+        let packet = match r.u8()? {
+            0 => Packet::Handshake { version: r.u16()? },
+            1 => Packet::Login { name: r.string()? },
+            2 => Packet::LoginSuccess { player_id: r.u32()?, position: r.vec3()?, view_distance: r.i32()? },
+            3 => Packet::Disconnect { reason: r.string()? },
+            4 => {
+                let (x, z) = (r.i32()?, r.i32()?);
+                let length = r.u32()? as usize;
+                Packet::ChunkData { x, z, data: r.bytes(length)?.to_vec() }
+            },
+            5 => Packet::UnloadChunk { x: r.i32()?, z: r.i32()? },
+            6 => {
+                let position = Vec3i::new(r.i32()?, r.i32()?, r.i32()?);
+                let id = r.u16()?;
+                let properties = r.bytes(3)?;
+                Packet::BlockChange { position, id, properties: [properties[0], properties[1], properties[2]] }
+            },
+            7 => Packet::PlayerPosition { player_id: r.u32()?, position: r.vec3()?, yaw: r.f32()?, pitch: r.f32()? },
+            8 => Packet::PlayerLeft { player_id: r.u32()? },
+            9 => Packet::Chat { message: r.string()? },
+            10 => Packet::BlockIds { keys: (0..r.u16()?).map(|_| r.string()).collect::<Result<Vec<String>, String>>()? },
+            id => return Err(format!("Unknown packet id {}", id))
+        };
+        if r.position != data.len() {
+            return Err(format!("{} trailing bytes after packet", data.len() - r.position));
+        }
+        Ok(packet)
+    }
+}
+
+struct PacketWriter {
+    data: Vec<u8>
+}
+
+impl PacketWriter {
+    fn u8(&mut self, value: u8) { self.data.push(value); }
+    fn u16(&mut self, value: u16) { self.data.extend_from_slice(&value.to_be_bytes()); }
+    fn u32(&mut self, value: u32) { self.data.extend_from_slice(&value.to_be_bytes()); }
+    fn i32(&mut self, value: i32) { self.data.extend_from_slice(&value.to_be_bytes()); }
+    fn f32(&mut self, value: f32) { self.u32(value.to_bits()); }
+
+    fn vec3(&mut self, value: &Vec3) {
+        self.f32(value.x);
+        self.f32(value.y);
+        self.f32(value.z);
+    }
+
+    fn string(&mut self, value: &str) -> Result<(), String> {
+        if value.len() > u16::max_value() as usize {
+            return Err(format!("String of {} bytes is too long to send", value.len()));
+        }
+        self.u16(value.len() as u16);
+        self.data.extend_from_slice(value.as_bytes());
+        Ok(())
+    }
+
+}
+
+struct PacketReader<'a> {
+    data: &'a [u8],
+    position: usize
+}
+
+impl<'a> PacketReader<'a> {
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+        if self.data.len() - self.position < count {
+            return Err("Packet ended unexpectedly".to_string());
+        }
+        let bytes = &self.data[self.position..self.position + count];
+        self.position += count;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> { Ok(self.bytes(1)?[0]) }
+    fn u16(&mut self) -> Result<u16, String> { let b = self.bytes(2)?; Ok(u16::from_be_bytes([b[0], b[1]])) }
+    fn u32(&mut self) -> Result<u32, String> { let b = self.bytes(4)?; Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]])) }
+    fn i32(&mut self) -> Result<i32, String> { Ok(self.u32()? as i32) }
+    fn f32(&mut self) -> Result<f32, String> { Ok(f32::from_bits(self.u32()?)) }
+
+    fn vec3(&mut self) -> Result<Vec3, String> {
+        Ok(Vec3::new(self.f32()?, self.f32()?, self.f32()?))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let length = self.u16()? as usize;
+        String::from_utf8(self.bytes(length)?.to_vec()).map_err(|_| "Invalid UTF-8 in string".to_string())
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use world::block::block_database;
+    use world::block::block_id_map::BlockIdMap;
+    use world::block::block_type::keys;
+
+    #[test]
+    fn round_trips_packets() {
+        let packet = Packet::Login { name: "tester".to_string() }.encode().unwrap();
+        assert_eq!(&packet[..4], &9u32.to_be_bytes());
+        match Packet::decode(&packet[4..]).unwrap() {
+            Packet::Login { name } => assert_eq!(name, "tester"),
+            packet => panic!("decoded {:?}", packet)
+        }
+    }
+
+    #[test]
+    fn rejects_block_changes_with_unknown_keys() {
+        let database = block_database::get();
+        let ids = BlockIdMap::from_keys(vec![keys::AIR.to_string(), keys::DIRT.to_string(), "minecraft:unknown".to_string()]);
+        let decode_change = |id: u16| {
+            let packet = Packet::BlockChange { position: Vec3i::new(1, 2, 3), id, properties: [0, 1, 0] }.encode().unwrap();
+            match Packet::decode(&packet[4..]).unwrap() {
+                Packet::BlockChange { id, properties, .. } => ids.state(id, properties, &database),
+                packet => panic!("decoded {:?}", packet)
+            }
+        };
+        assert_eq!(decode_change(1).unwrap().block_type, database.block_type(keys::DIRT).unwrap());
+        assert!(decode_change(2).unwrap_err().contains("minecraft:unknown"));
+        assert!(decode_change(3).unwrap_err().contains("Unknown block id"));
+    }
+
+    #[test]
+    fn rejects_oversized_strings_and_lists() {
+        let message = "a".repeat(u16::max_value() as usize + 1);
+        assert!(Packet::Chat { message }.encode().is_err());
+        let keys = vec!["minecraft:stone".to_string(); u16::max_value() as usize + 1];
+        assert!(Packet::BlockIds { keys }.encode().is_err());
+        let data = vec![0; MAX_PACKET_SIZE];
+        assert!(Packet::ChunkData { x: 0, z: 0, data }.encode().is_err());
+    }
+}
\ No newline at end of file
@@ -1,12 +1,20 @@
 use math::{Vec3, vec3};
-use util::controls::{Controls, KeyState, KeyAction};
+use input::controls::{Controls, KeyState, KeyAction, HOTBAR};
 use std::ops::{Sub, Add};
 use camera::Camera;
-use util::text::Text;
+use input::events::InputFrame;
+use ui::text::Text;
+use util::timer::TICK_MILLIS;
 use window::Window;
+use world::block::block_type::BlockType;
+use world::block::block_database;
 
 pub const CROUCH_HEIGHT_DECREASE: f32 = 0.44;
 pub const FRAME_CONSTANT: f32 = 200.0;
+pub const PLACEABLE_BLOCKS: [&'static str; 6] = [
+    "minecraft:pumpkin", "minecraft:jack_o_lantern", "minecraft:torch",
+    "minecraft:stone", "minecraft:dirt", "minecraft:gravel"
+];
 
 impl Player {
     pub fn new() -> Player { Default::default() }
@@ -16,14 +24,28 @@ impl Player {
 
     pub fn height_vector(&self) -> Vec3 { vec3(0.0, self.height, 0.0) }
 
-    pub fn set_frame_leap(&mut self, leap: u32) { self.frame_leap = leap; }
-
     pub fn is_sprinting(&self) -> bool { self.is_sprinting }
     pub fn set_sprinting(&mut self, is: bool) { self.is_sprinting = is; }
 
     pub fn is_crouched(&self) -> bool { self.is_crouched }
     pub fn set_crouched(&mut self, is: bool) { self.is_crouched = is; }
 
+    pub fn controls(&self) -> &Controls { &self.controls }
+    pub fn controls_mut(&mut self) -> &mut Controls { &mut self.controls }
+    pub fn set_controls(&mut self, controls: Controls) { self.controls = controls; }
+
+    pub fn selected_block(&self) -> Option<BlockType> {
+        block_database::get().block_type(PLACEABLE_BLOCKS[self.selected_block])
+    }
+
+    pub fn draw_inventory(&self, text: &mut Text, window: &Window) {
+        let top = window.size().1 as f32 / 2.0 + PLACEABLE_BLOCKS.len() as f32 * 10.0;
+        for (slot, key) in PLACEABLE_BLOCKS.iter().enumerate() {
+            let color = if slot == self.selected_block { vector!(1.0, 1.0, 0.0) } else { vector!(0.8, 0.8, 0.8) };
+            text.render(&format!("{}: {}", slot + 1, key), 10.0, top - slot as f32 * 20.0, 0.4, &color);
+        }
+    }
+
     pub fn draw_position(&self, text: &mut Text) {
         text.render(
             &format!("X: {} | Y: {} | Z: {}", self.position.x as i32, self.position.y as i32, self.position.z as i32),
@@ -35,19 +57,19 @@ impl Player {
         (self.move_speed * match self.is_sprinting {
             true => self.sprint_scale,
             false => 1.0
-        }) * self.frame_leap as f32 / FRAME_CONSTANT
+        }) * TICK_MILLIS / FRAME_CONSTANT
     }
 
     pub fn back_speed(&self) -> f32 {
-        self.move_speed * self.frame_leap as f32 / FRAME_CONSTANT
+        self.move_speed * TICK_MILLIS / FRAME_CONSTANT
     }
 
     pub fn strafe_speed(&self) -> f32 {
-        self.strafe_speed * self.frame_leap as f32 / FRAME_CONSTANT
+        self.strafe_speed * TICK_MILLIS / FRAME_CONSTANT
     }
 
-    pub fn update(&mut self, camera: &mut Camera, window: &::glfw::Window) {
-        self.controls.update(window);
+    pub fn update(&mut self, camera: &mut Camera, frame: &InputFrame) {
+        self.controls.update(frame);
         let true_forward = self.true_froward();
         let true_back = self.true_back();
         let true_strafe = self.true_strafe();
@@ -66,6 +88,16 @@ impl Player {
             self.velocity = self.velocity.add(*camera.right() * true_strafe);
         }
 
+        let analog = self.controls.gamepad().movement();
+        if analog.y != 0.0 {
+            let speed = if analog.y > 0.0 { true_forward } else { true_back };
+            self.velocity = self.velocity.add(*camera.forward() * (speed * analog.y));
+        }
+        if analog.x != 0.0 {
+            self.velocity = self.velocity.add(*camera.right() * (true_strafe * analog.x));
+        }
+        camera.look(self.controls.gamepad().look() * (TICK_MILLIS / 1000.0));
+
         if self.controls.key_state(KeyAction::Crouch) == &KeyState::Active {
             self.set_crouched(true);
             height_vec.y -= CROUCH_HEIGHT_DECREASE;
@@ -73,14 +105,29 @@ impl Player {
 
         self.is_sprinting = self.controls.key_state(KeyAction::Sprint) == &KeyState::Active;
 
-        let (x, y) = window.get_cursor_pos();
+        if self.controls.key_state(KeyAction::CycleBlock) == &KeyState::Clicked {
+            self.selected_block = (self.selected_block + 1) % PLACEABLE_BLOCKS.len();
+        }
+        let (_, scroll) = frame.events.scroll();
+        if scroll != 0.0 {
+            let count = PLACEABLE_BLOCKS.len() as i32;
+            let step = if scroll < 0.0 { 1 } else { -1 };
+            self.selected_block = ((self.selected_block as i32 + step + count) % count) as usize;
+        }
+        for (slot, &action) in HOTBAR.iter().enumerate().take(PLACEABLE_BLOCKS.len()) {
+            if self.controls.key_state(action) == &KeyState::Active {
+                self.selected_block = slot;
+            }
+        }
+
+        let (x, y) = frame.cursor;
         camera.update(&self.velocity, &height_vec, x, y);
         self.velocity = ::math::Zero::zero();
         self.position = camera.position().clone();
     }
 
     fn true_froward(&self) -> f32 {
-        (self.frame_leap as f32 / FRAME_CONSTANT) * (
+        (TICK_MILLIS / FRAME_CONSTANT) * (
             if self.is_sprinting { self.sprint_scale } else { 1.0 }
                 * self.move_speed
                 - (if self.is_crouched { self.crouch_slow } else { 0.0 })
@@ -88,13 +135,13 @@ impl Player {
     }
 
     fn true_back(&self) -> f32 {
-        (self.frame_leap as f32 / FRAME_CONSTANT) * (
+        (TICK_MILLIS / FRAME_CONSTANT) * (
             self.move_speed - (if self.is_crouched { self.crouch_slow } else { 0.0 })
         )
     }
 
     fn true_strafe(&self) -> f32 {
-        (self.frame_leap as f32 / FRAME_CONSTANT) * (
+        (TICK_MILLIS / FRAME_CONSTANT) * (
             self.strafe_speed - (if self.is_crouched { self.crouch_slow } else { 0.0 })
         )
     }
@@ -110,10 +157,10 @@ pub struct Player {
     is_sprinting: bool,
     is_crouched: bool,
     jump_height: f32,
-    frame_leap: u32,
     position: Vec3,
     velocity: Vec3,
     controls: Controls,
+    selected_block: usize,
 }
 
 impl Default for Player {
@@ -128,10 +175,10 @@ impl Default for Player {
             is_sprinting: false,
             is_crouched: false,
             jump_height: 1.88,
-            frame_leap: 0,
             position: ::math::Zero::zero(),
             velocity: ::math::Zero::zero(),
             controls: Controls::default(),
+            selected_block: 0,
         }
     }
 }
\ No newline at end of file
@@ -14,6 +14,7 @@ pub struct BlockBuffer {
     vao: u32,
     vbo: u32,
     ibo: u32,
+    index_count: i32,
     block_type: BlockType
 }
 
@@ -21,21 +22,7 @@ impl BlockBuffer {
     pub fn new(gl: &Gl, block: Arc<Block>) -> BlockBuffer {
         let (mut vbo, mut vao, mut ibo) = (0, 0, 0);
 
-        let funcs = vec![
-            block.build_front_face(&Vec3i::new(0, 0, 0), 0, false),
-            block.build_back_face(&Vec3i::new(0, 0, 0), 4, false),
-            block.build_left_face(&Vec3i::new(0, 0, 0), 8, false),
-            block.build_right_face(&Vec3i::new(0, 0, 0), 12, false),
-            block.build_top_face(&Vec3i::new(0, 0, 0), 16, false),
-            block.build_bottom_face(&Vec3i::new(0, 0, 0), 20, false)
-        ];
-        let mut verts = Vec::new();
-        let mut inds = Vec::new();
-
-        for (mut v, mut i) in funcs {
-            verts.append(&mut v);
-            inds.append(&mut i);
-        }
+        let (verts, inds) = block.build(&Vec3i::new(0, 0, 0), 0, &|_| false);
 
         unsafe {
             gl.GenVertexArrays(1, &mut vao);
@@ -57,16 +44,19 @@ impl BlockBuffer {
 
             gl.EnableVertexAttribArray(2);
             gl.VertexAttribPointer(2, 3, GL::FLOAT, GL::FALSE, size_of::<Vertex>() as i32, (5 * size_of::<f32>()) as *const ::std::ffi::c_void);
+
+            gl.EnableVertexAttribArray(3);
+            gl.VertexAttribPointer(3, 1, GL::FLOAT, GL::FALSE, size_of::<Vertex>() as i32, (8 * size_of::<f32>()) as *const ::std::ffi::c_void);
         }
 
-        BlockBuffer { gl: gl.clone(), vao, vbo, ibo, block_type: block.m_type }
+        BlockBuffer { gl: gl.clone(), vao, vbo, ibo, index_count: inds.len() as i32, block_type: block.m_type }
     }
 
     pub fn draw(&self, shader: &Shader, model: &Mat4) {
         unsafe {
             shader.mat_4("model", model);
             self.gl.BindVertexArray(self.vao);
-            self.gl.DrawElements(GL::TRIANGLES, 36, GL::UNSIGNED_INT, ::std::ptr::null());
+            self.gl.DrawElements(GL::TRIANGLES, self.index_count, GL::UNSIGNED_INT, ::std::ptr::null());
             self.gl.BindVertexArray(0);
         }
     }
@@ -0,0 +1,120 @@
+use GL;
+use GL::Gl;
+use std::collections::HashMap;
+use world::block::texture_array;
+
+const MAX_ANISOTROPY: f32 = 16.0;
+
+pub struct BlockTexture {
+    id: u32,
+    gl: Gl,
+    frames: Vec<u32>,
+    frame_mips: HashMap<u32, Vec<MipLevel>>
+}
+
+struct MipLevel {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>
+}
+
+fn mip_chain(pixels: &[u8], width: u32, height: u32) -> Vec<MipLevel> {
+    let mut levels: Vec<MipLevel> = Vec::new();
+    let (mut source, mut w, mut h) = (pixels.to_vec(), width, height);
+    while w > 1 || h > 1 {
+        let (nw, nh) = ((w / 2).max(1), (h / 2).max(1));
+        let mut next = vec![0u8; (nw * nh * 4) as usize];
+        for y in 0..nh {
+            for x in 0..nw {
+                for c in 0..4 {
+                    let mut sum = 0u32;
+                    for &(dx, dy) in &[(0, 0), (1, 0), (0, 1), (1, 1)] {
+                        let (sx, sy) = ((x * 2 + dx).min(w - 1), (y * 2 + dy).min(h - 1));
+                        sum += source[((sy * w + sx) * 4 + c) as usize] as u32;
+                    }
+                    next[((y * nw + x) * 4 + c) as usize] = (sum / 4) as u8;
+                }
+            }
+        }
+        levels.push(MipLevel { width: nw, height: nh, pixels: next.clone() });
+        source = next;
+        w = nw;
+        h = nh;
+    }
+    levels
+}
+
+impl BlockTexture {
+    pub fn new(gl: &Gl) -> BlockTexture {
+        let mut id = 0;
+        let textures = texture_array::get();
+        let (width, height) = textures.tile_size();
+        unsafe {
+            gl.GenTextures(1, &mut id);
+            gl.BindTexture(GL::TEXTURE_2D_ARRAY, id);
+            gl.TexImage3D(GL::TEXTURE_2D_ARRAY, 0, GL::RGBA8 as i32, width as i32, height as i32, textures.layer_count() as i32,
+                          0, GL::RGBA, GL::UNSIGNED_BYTE, textures.pixels().as_ptr() as *const ::std::ffi::c_void);
+            gl.GenerateMipmap(GL::TEXTURE_2D_ARRAY);
+            gl.TexParameteri(GL::TEXTURE_2D_ARRAY, GL::TEXTURE_WRAP_S, GL::REPEAT as i32);
+            gl.TexParameteri(GL::TEXTURE_2D_ARRAY, GL::TEXTURE_WRAP_T, GL::REPEAT as i32);
+            gl.TexParameteri(GL::TEXTURE_2D_ARRAY, GL::TEXTURE_MIN_FILTER, GL::LINEAR_MIPMAP_LINEAR as i32);
+            gl.TexParameteri(GL::TEXTURE_2D_ARRAY, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
+
+            let mut max_anisotropy = 0.0;
+            gl.GetFloatv(GL::MAX_TEXTURE_MAX_ANISOTROPY_EXT, &mut max_anisotropy);
+            if max_anisotropy > 0.0 {
+                gl.TexParameterf(GL::TEXTURE_2D_ARRAY, GL::TEXTURE_MAX_ANISOTROPY_EXT, max_anisotropy.min(MAX_ANISOTROPY));
+            }
+            gl.BindTexture(GL::TEXTURE_2D_ARRAY, 0);
+        }
+
+        let frames = textures.animated_layers().iter().map(|a| a.frame_at(0)).collect();
+        let mut frame_mips = HashMap::new();
+        for layer in textures.animated_layers().iter().flat_map(|a| a.frame_layers()) {
+            frame_mips.entry(layer).or_insert_with(|| mip_chain(textures.layer_pixels(layer), width, height));
+        }
+        BlockTexture {id, gl: gl.clone(), frames, frame_mips}
+    }
+
+    pub fn animate(&mut self, time: u64) {
+        let textures = texture_array::get();
+        let (width, height) = textures.tile_size();
+        let mut changed = false;
+        for (animation, current) in textures.animated_layers().iter().zip(self.frames.iter_mut()) {
+            let frame = animation.frame_at(time);
+            if frame == *current { continue; }
+            *current = frame;
+            changed = true;
+            unsafe {
+                self.gl.BindTexture(GL::TEXTURE_2D_ARRAY, self.id);
+                self.gl.TexSubImage3D(GL::TEXTURE_2D_ARRAY, 0, 0, 0, animation.layer as i32, width as i32, height as i32, 1,
+                                      GL::RGBA, GL::UNSIGNED_BYTE, textures.layer_pixels(frame).as_ptr() as *const ::std::ffi::c_void);
+                for (level, mip) in self.frame_mips[&frame].iter().enumerate() {
+                    self.gl.TexSubImage3D(GL::TEXTURE_2D_ARRAY, level as i32 + 1, 0, 0, animation.layer as i32,
+                                          mip.width as i32, mip.height as i32, 1,
+                                          GL::RGBA, GL::UNSIGNED_BYTE, mip.pixels.as_ptr() as *const ::std::ffi::c_void);
+                }
+            }
+        }
+        if changed {
+            unsafe {
+                self.gl.BindTexture(GL::TEXTURE_2D_ARRAY, 0);
+            }
+        }
+    }
+
+    pub fn bind(&self, unit: u32) {
+        unsafe {
+            self.gl.ActiveTexture(GL::TEXTURE0 + unit);
+            self.gl.BindTexture(GL::TEXTURE_2D_ARRAY, self.id);
+        }
+    }
+}
+
+impl Drop for BlockTexture {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.DeleteTextures(1, &mut self.id);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,98 @@
+use GL;
+use GL::Gl;
+use png;
+use png::HasParameters;
+use std::ffi::c_void;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub enum Encoding {
+    Color,
+    Normal,
+    Position
+}
+
+pub fn read_framebuffer(gl: &Gl, size: (i32, i32)) -> Vec<u8> {
+    let (width, height) = size;
+    let mut pixels: Vec<u8> = vec![0; (width * height * 4) as usize];
+    unsafe {
+        gl.BindFramebuffer(GL::READ_FRAMEBUFFER, 0);
+        gl.ReadBuffer(GL::BACK);
+        gl.PixelStorei(GL::PACK_ALIGNMENT, 1);
+        gl.ReadPixels(0, 0, width, height, GL::RGBA, GL::UNSIGNED_BYTE, pixels.as_mut_ptr() as *mut c_void);
+    }
+    flip_rows(&pixels, width as usize * 4)
+}
+
+pub fn read_texture(gl: &Gl, texture: u32, size: (i32, i32), encoding: &Encoding) -> Vec<u8> {
+    let (width, height) = size;
+    let mut data: Vec<f32> = vec![0.0; (width * height * 4) as usize];
+    unsafe {
+        gl.BindTexture(GL::TEXTURE_2D, texture);
+        gl.PixelStorei(GL::PACK_ALIGNMENT, 1);
+        gl.GetTexImage(GL::TEXTURE_2D, 0, GL::RGBA, GL::FLOAT, data.as_mut_ptr() as *mut c_void);
+        gl.BindTexture(GL::TEXTURE_2D, 0);
+    }
+    let pixels = data.chunks(4).flat_map(|p| {
+        let rgb = match *encoding {
+            Encoding::Color => [p[0], p[1], p[2]],
+            Encoding::Normal => [p[0] * 0.5 + 0.5, p[1] * 0.5 + 0.5, p[2] * 0.5 + 0.5],
+            Encoding::Position => [wrap(p[0]), wrap(p[1]), wrap(p[2])]
+        };
+        vec![to_byte(rgb[0]), to_byte(rgb[1]), to_byte(rgb[2]), 255]
+    }).collect::<Vec<u8>>();
+    flip_rows(&pixels, width as usize * 4)
+}
+
+pub fn save_png(path: &Path, size: (i32, i32), pixels: &[u8]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create directory {}: {}", dir.display(), e))?;
+    }
+    let file = File::create(path)
+        .map_err(|e| format!("Failed to create file {}: {}", path.display(), e))?;
+
+    let mut encoder = png::Encoder::new(BufWriter::new(file), size.0 as u32, size.1 as u32);
+    encoder.set(png::ColorType::RGBA).set(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()
+        .map_err(|e| format!("Failed to write png header {}: {}", path.display(), e))?;
+    writer.write_image_data(pixels)
+        .map_err(|e| format!("Failed to write png data {}: {}", path.display(), e))
+}
+
+pub fn timestamp() -> String {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH)
+        .expect("Time went backwards");
+    let secs = since_epoch.as_secs() as i64;
+    let (days, day_secs) = (secs / 86_400, secs % 86_400);
+
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}_{:02}-{:02}-{:02}_{:03}",
+            year, month, day,
+            day_secs / 3_600, (day_secs % 3_600) / 60, day_secs % 60,
+            since_epoch.subsec_millis())
+}
+
+fn flip_rows(pixels: &[u8], stride: usize) -> Vec<u8> {
+    pixels.chunks(stride).rev().flat_map(|row| row.iter().cloned()).collect()
+}
+
+fn wrap(position: f32) -> f32 {
+    let scaled = position / 16.0;
+    scaled - scaled.floor()
+}
+
+fn to_byte(value: f32) -> u8 {
+    (value.max(0.0).min(1.0) * 255.0).round() as u8
+}
@@ -40,6 +40,9 @@ impl ChunkBuffer {
 
             gl.EnableVertexAttribArray(2);
             gl.VertexAttribPointer(2, 3, GL::FLOAT, GL::FALSE, size_of::<Vertex>() as i32, (5 * size_of::<f32>()) as *const ::std::ffi::c_void);
+
+            gl.EnableVertexAttribArray(3);
+            gl.VertexAttribPointer(3, 1, GL::FLOAT, GL::FALSE, size_of::<Vertex>() as i32, (8 * size_of::<f32>()) as *const ::std::ffi::c_void);
         }
 
         ChunkBuffer { gl: gl.clone(), vao, vbo, ibo, index_count: inds.len() as i32 }
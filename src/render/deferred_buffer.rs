@@ -2,27 +2,20 @@ use GL;
 use GL::Gl;
 use shader::{Shader, Type as ShaderType};
 use math::{Mat4, Vec3, One, Ext::{translate, scale}};
-use std::mem::size_of;
-use std::ptr::null as NULL;
-use std::ffi::c_void;
 use world::block::block_light::BlockLight;
 use camera::Camera;
 use world::block::block::Block;
-use world::block::block_buffer::BlockBuffer;
+use render::block_buffer::BlockBuffer;
 use world::block::block_database;
-
-lazy_static! {
-    static ref quad: [f32; 20] = [
-        -1.0, 1.0, 0.0, 0.0, 1.0,
-        -1.0, -1.0, 0.0, 0.0, 0.0,
-        1.0, 1.0, 0.0, 1.0, 1.0,
-        1.0, -1.0, 0.0, 1.0, 0.0
-    ];
-}
+use render::capture;
+use render::screen_quad::ScreenQuad;
+use render::fog::FogSettings;
+use std::path::Path;
 
 const CONSTANT: f32 = 1.0;
 const LINEAR: f32 = 0.7;
 const QUADRATIC: f32 = 1.8;
+const EMISSIVE_STRENGTH: f32 = 3.0;
 
 pub struct DeferredBuffer {
     gl: Gl,
@@ -31,8 +24,7 @@ pub struct DeferredBuffer {
     g_normal: u32,
     g_albedo: u32,
     rbo_depth: u32,
-    vao: u32,
-    vbo: u32,
+    quad: ScreenQuad,
     shader: Shader,
     framebuffer_size: (i32, i32)
 }
@@ -40,21 +32,9 @@ pub struct DeferredBuffer {
 impl DeferredBuffer {
     pub fn new(gl: &Gl, framebuffer_size: (i32, i32)) -> DeferredBuffer {
         let (width, height) = framebuffer_size;
-        let (mut g_buffer, mut g_position, mut g_normal, mut g_albedo, mut rbo_depth, mut vao, mut vbo) = (0, 0, 0, 0, 0, 0, 0);
+        let (mut g_buffer, mut g_position, mut g_normal, mut g_albedo, mut rbo_depth) = (0, 0, 0, 0, 0);
 
         unsafe {
-            gl.GenVertexArrays(1, &mut vao);
-            gl.GenBuffers(1, &mut vbo);
-            gl.BindVertexArray(vao);
-            gl.BindBuffer(GL::ARRAY_BUFFER, vbo);
-            gl.BufferData(GL::ARRAY_BUFFER, (size_of::<f32>() * quad.len()) as isize, quad.as_ptr() as *const c_void, GL::STATIC_DRAW);
-            gl.EnableVertexAttribArray(0);
-            gl.VertexAttribPointer(0, 3, GL::FLOAT, GL::FALSE, (5 * size_of::<f32>()) as i32, NULL());
-            gl.EnableVertexAttribArray(1);
-            gl.VertexAttribPointer(1, 2, GL::FLOAT, GL::FALSE, (5 * size_of::<f32>()) as i32, (3 * size_of::<f32>()) as *const c_void);
-            gl.BindVertexArray(0);
-            gl.BindBuffer(GL::ARRAY_BUFFER, 0);
-
             gl.GenFramebuffers(1, &mut g_buffer);
             gl.BindFramebuffer(GL::FRAMEBUFFER, g_buffer);
 
@@ -99,10 +79,11 @@ impl DeferredBuffer {
         shader.int("gPosition", 0);
         shader.int("gNormal", 1);
         shader.int("gAlbedoSpec", 2);
+        shader.int("ssao", 3);
 
         DeferredBuffer {
             gl: gl.clone(), g_buffer, g_position,
-            g_normal, g_albedo, rbo_depth, vao, vbo, shader, framebuffer_size
+            g_normal, g_albedo, rbo_depth, quad: ScreenQuad::new(gl), shader, framebuffer_size
         }
     }
 
@@ -110,13 +91,8 @@ impl DeferredBuffer {
         &self.shader
     }
 
-    fn draw_quad(&self) {
-        unsafe {
-            self.gl.BindVertexArray(self.vao);
-            self.gl.DrawArrays(GL::TRIANGLE_STRIP, 0, 4);
-            self.gl.BindVertexArray(0);
-        }
-    }
+    pub fn position_texture(&self) -> u32 { self.g_position }
+    pub fn normal_texture(&self) -> u32 { self.g_normal }
 
     pub fn bind_framebuffer(&self) {
         unsafe {
@@ -132,7 +108,7 @@ impl DeferredBuffer {
         }
     }
 
-    pub fn apply_lighting(&self, view_pos: &Vec3, lights: &Vec<&BlockLight>) {
+    pub fn apply_lighting(&self, view_pos: &Vec3, lights: &Vec<&BlockLight>, fog: &FogSettings, occlusion: Option<u32>) {
         self.shader.bind();
         unsafe {
             self.gl.ActiveTexture(GL::TEXTURE0);
@@ -141,7 +117,11 @@ impl DeferredBuffer {
             self.gl.BindTexture(GL::TEXTURE_2D, self.g_normal);
             self.gl.ActiveTexture(GL::TEXTURE2);
             self.gl.BindTexture(GL::TEXTURE_2D, self.g_albedo);
+            self.gl.ActiveTexture(GL::TEXTURE3);
+            self.gl.BindTexture(GL::TEXTURE_2D, occlusion.unwrap_or(0));
+            self.gl.ActiveTexture(GL::TEXTURE0);
         }
+        self.shader.int("ssaoEnabled", occlusion.is_some() as i32);
         for (i, light) in lights.iter().enumerate() {
             let l = (7.0 / light.strength) * LINEAR;
             let q = (7.0 / light.strength) * QUADRATIC;
@@ -154,20 +134,37 @@ impl DeferredBuffer {
             self.shader.float(&format!("lights[{}].radius", i), radius);
         }
         self.shader.vec3("viewPos", view_pos);
-        self.draw_quad();
+        self.shader.vec3("fogColor", &fog.color);
+        self.shader.float("fogDensity", fog.density);
+        self.quad.draw();
     }
 
-    pub fn copy_depth_buffer(&self) {
+    pub fn copy_depth_buffer(&self, target: u32) {
         unsafe {
             self.gl.BindFramebuffer(GL::READ_FRAMEBUFFER, self.g_buffer);
-            self.gl.BindFramebuffer(GL::DRAW_FRAMEBUFFER, 0);
+            self.gl.BindFramebuffer(GL::DRAW_FRAMEBUFFER, target);
             self.gl.BlitFramebuffer(0, 0,
                 self.framebuffer_size.0, self.framebuffer_size.1,
                 0, 0,
                 self.framebuffer_size.0, self.framebuffer_size.1,
                 GL::DEPTH_BUFFER_BIT, GL::NEAREST);
-            self.gl.BindFramebuffer(GL::FRAMEBUFFER, 0);
+            self.gl.BindFramebuffer(GL::FRAMEBUFFER, target);
+        }
+    }
+
+    pub fn capture_attachments(&self, directory: &Path, name: &str) -> Result<(), String> {
+        let attachments = [
+            ("position", self.g_position, capture::Encoding::Position),
+            ("normal", self.g_normal, capture::Encoding::Normal),
+            ("albedo", self.g_albedo, capture::Encoding::Color)
+        ];
+        for &(attachment, texture, ref encoding) in attachments.iter() {
+            let pixels = capture::read_texture(&self.gl, texture, self.framebuffer_size, encoding);
+            capture::save_png(
+                &directory.join(format!("{}_{}.png", name, attachment)),
+                self.framebuffer_size, &pixels)?;
         }
+        Ok(())
     }
 
     pub fn render_lighting(&self, camera: &Camera, shader: &Shader, lights: &Vec<(&BlockLight, &BlockBuffer)>) {
@@ -177,6 +174,7 @@ impl DeferredBuffer {
         for (light, buffer) in lights.iter() {
             let block = block_database::get().get_block(light.block_type);
             shader.vec3("lightColor", &light.color);
+            shader.float("emission", EMISSIVE_STRENGTH);
             let block_scale = block.model_scale.unwrap_or(1.0);
             buffer.draw(shader, &scale(
                 &block.get_model(&light.position, 0.0, 0.0),
@@ -0,0 +1,68 @@
+use util::math::{Vec3, vec3};
+use world::constants::{CHUNK_SIZE, RENDER_DISTANCE, SEA_LEVEL};
+
+const EDGE_VISIBILITY: f32 = 0.02;
+
+#[derive(Copy, Clone, Debug)]
+pub struct FogSettings {
+    pub color: Vec3,
+    pub density: f32
+}
+
+pub trait FogRegion {
+    fn settings(&self, position: &Vec3, base: &FogSettings) -> Option<FogSettings>;
+}
+
+pub struct BelowHeight {
+    pub height: f32,
+    pub density_scale: f32,
+    pub color: Vec3
+}
+
+impl FogRegion for BelowHeight {
+    fn settings(&self, position: &Vec3, base: &FogSettings) -> Option<FogSettings> {
+        if position.y >= self.height { return None; }
+        Some(FogSettings { color: self.color, density: base.density * self.density_scale })
+    }
+}
+
+pub struct Fog {
+    base: FogSettings,
+    regions: Vec<Box<dyn FogRegion>>
+}
+
+impl Fog {
+    pub fn new(color: Vec3) -> Fog {
+        let mut fog = Fog {
+            base: FogSettings { color, density: Fog::density_for_distance(RENDER_DISTANCE) },
+            regions: Vec::new()
+        };
+        fog.add_region(Box::new(BelowHeight {
+            height: SEA_LEVEL as f32,
+            density_scale: 6.0,
+            color: vec3(0.05, 0.05, 0.07)
+        }));
+        fog
+    }
+
+    pub fn density_for_distance(render_distance: i32) -> f32 {
+        let distance = (render_distance * CHUNK_SIZE) as f32;
+        (-EDGE_VISIBILITY.ln()).sqrt() / distance
+    }
+
+    pub fn set_color(&mut self, color: Vec3) { self.base.color = color; }
+    pub fn set_render_distance(&mut self, render_distance: i32) {
+        self.base.density = Fog::density_for_distance(render_distance);
+    }
+
+    pub fn add_region(&mut self, region: Box<dyn FogRegion>) {
+        self.regions.push(region);
+    }
+
+    pub fn settings_at(&self, position: &Vec3) -> FogSettings {
+        self.regions.iter()
+            .filter_map(|r| r.settings(position, &self.base))
+            .next()
+            .unwrap_or(self.base)
+    }
+}
@@ -0,0 +1,171 @@
+use GL;
+use GL::Gl;
+use shader::{Shader, Type as ShaderType};
+use render::screen_quad::ScreenQuad;
+
+const BLOOM_THRESHOLD: f32 = 1.0;
+const BLOOM_PASSES: u32 = 10;
+pub const DEFAULT_EXPOSURE: f32 = 1.2;
+pub const DEFAULT_BLOOM_STRENGTH: f32 = 0.6;
+
+pub struct HdrBuffer {
+    gl: Gl,
+    fbo: u32,
+    color: u32,
+    rbo_depth: u32,
+    bloom_fbos: [u32; 2],
+    bloom_textures: [u32; 2],
+    quad: ScreenQuad,
+    bright_shader: Shader,
+    blur_shader: Shader,
+    tonemap_shader: Shader,
+    exposure: f32,
+    bloom_strength: f32,
+    framebuffer_size: (i32, i32)
+}
+
+impl HdrBuffer {
+    pub fn new(gl: &Gl, framebuffer_size: (i32, i32)) -> HdrBuffer {
+        let (width, height) = framebuffer_size;
+        let (bloom_width, bloom_height) = ((width / 2).max(1), (height / 2).max(1));
+        let (mut fbo, mut color, mut rbo_depth) = (0, 0, 0);
+        let (mut bloom_fbos, mut bloom_textures) = ([0u32; 2], [0u32; 2]);
+
+        unsafe {
+            gl.GenFramebuffers(1, &mut fbo);
+            gl.BindFramebuffer(GL::FRAMEBUFFER, fbo);
+
+            gl.GenTextures(1, &mut color);
+            gl.BindTexture(GL::TEXTURE_2D, color);
+            gl.TexImage2D(GL::TEXTURE_2D, 0, GL::RGBA16F as i32, width, height, 0, GL::RGBA, GL::FLOAT, ::std::ptr::null());
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::LINEAR as i32);
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::LINEAR as i32);
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+            gl.FramebufferTexture2D(GL::FRAMEBUFFER, GL::COLOR_ATTACHMENT0, GL::TEXTURE_2D, color, 0);
+
+            gl.GenRenderbuffers(1, &mut rbo_depth);
+            gl.BindRenderbuffer(GL::RENDERBUFFER, rbo_depth);
+            gl.RenderbufferStorage(GL::RENDERBUFFER, GL::DEPTH_COMPONENT, width, height);
+            gl.FramebufferRenderbuffer(GL::FRAMEBUFFER, GL::DEPTH_ATTACHMENT, GL::RENDERBUFFER, rbo_depth);
+            if gl.CheckFramebufferStatus(GL::FRAMEBUFFER) != GL::FRAMEBUFFER_COMPLETE {
+                println!("HDR framebuffer not complete!");
+            }
+
+            gl.GenFramebuffers(2, bloom_fbos.as_mut_ptr());
+            gl.GenTextures(2, bloom_textures.as_mut_ptr());
+            for i in 0..2 {
+                gl.BindFramebuffer(GL::FRAMEBUFFER, bloom_fbos[i]);
+                gl.BindTexture(GL::TEXTURE_2D, bloom_textures[i]);
+                gl.TexImage2D(GL::TEXTURE_2D, 0, GL::RGBA16F as i32, bloom_width, bloom_height, 0, GL::RGBA, GL::FLOAT, ::std::ptr::null());
+                gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::LINEAR as i32);
+                gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::LINEAR as i32);
+                gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+                gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+                gl.FramebufferTexture2D(GL::FRAMEBUFFER, GL::COLOR_ATTACHMENT0, GL::TEXTURE_2D, bloom_textures[i], 0);
+                if gl.CheckFramebufferStatus(GL::FRAMEBUFFER) != GL::FRAMEBUFFER_COMPLETE {
+                    println!("Bloom framebuffer not complete!");
+                }
+            }
+            gl.BindTexture(GL::TEXTURE_2D, 0);
+            gl.BindFramebuffer(GL::FRAMEBUFFER, 0);
+        }
+
+        let bright_shader = Shader::new(gl, ShaderType::BrightPass, false).unwrap();
+        bright_shader.bind();
+        bright_shader.int("scene", 0);
+        bright_shader.float("threshold", BLOOM_THRESHOLD);
+
+        let blur_shader = Shader::new(gl, ShaderType::Blur, false).unwrap();
+        blur_shader.bind();
+        blur_shader.int("image", 0);
+
+        let tonemap_shader = Shader::new(gl, ShaderType::Tonemap, false).unwrap();
+        tonemap_shader.bind();
+        tonemap_shader.int("scene", 0);
+        tonemap_shader.int("bloom", 1);
+
+        HdrBuffer {
+            gl: gl.clone(), fbo, color, rbo_depth, bloom_fbos, bloom_textures,
+            quad: ScreenQuad::new(gl), bright_shader, blur_shader, tonemap_shader,
+            exposure: DEFAULT_EXPOSURE, bloom_strength: DEFAULT_BLOOM_STRENGTH,
+            framebuffer_size
+        }
+    }
+
+    pub fn framebuffer(&self) -> u32 { self.fbo }
+
+    pub fn exposure(&self) -> f32 { self.exposure }
+    pub fn set_exposure(&mut self, exposure: f32) { self.exposure = exposure.max(0.05); }
+
+    pub fn bloom_strength(&self) -> f32 { self.bloom_strength }
+    pub fn set_bloom_strength(&mut self, strength: f32) { self.bloom_strength = strength.max(0.0); }
+
+    pub fn bind_framebuffer(&self) {
+        unsafe {
+            self.gl.BindFramebuffer(GL::FRAMEBUFFER, self.fbo);
+            self.gl.Clear(GL::COLOR_BUFFER_BIT | GL::DEPTH_BUFFER_BIT);
+        }
+    }
+
+    pub fn unbind_framebuffer(&self) {
+        unsafe {
+            self.gl.BindFramebuffer(GL::FRAMEBUFFER, 0);
+        }
+    }
+
+    pub fn apply_bloom(&self) {
+        let (width, height) = self.framebuffer_size;
+        unsafe {
+            self.gl.Disable(GL::DEPTH_TEST);
+            self.gl.Viewport(0, 0, (width / 2).max(1), (height / 2).max(1));
+
+            self.gl.BindFramebuffer(GL::FRAMEBUFFER, self.bloom_fbos[0]);
+            self.bright_shader.bind();
+            self.gl.ActiveTexture(GL::TEXTURE0);
+            self.gl.BindTexture(GL::TEXTURE_2D, self.color);
+            self.quad.draw();
+
+            self.blur_shader.bind();
+            for pass in 0..BLOOM_PASSES {
+                let horizontal = (pass % 2 == 0) as usize;
+                self.gl.BindFramebuffer(GL::FRAMEBUFFER, self.bloom_fbos[horizontal]);
+                self.blur_shader.int("horizontal", horizontal as i32);
+                self.gl.BindTexture(GL::TEXTURE_2D, self.bloom_textures[1 - horizontal]);
+                self.quad.draw();
+            }
+
+            self.gl.BindFramebuffer(GL::FRAMEBUFFER, 0);
+            self.gl.Viewport(0, 0, width, height);
+            self.gl.Enable(GL::DEPTH_TEST);
+        }
+    }
+
+    pub fn render(&self) {
+        self.tonemap_shader.bind();
+        self.tonemap_shader.float("exposure", self.exposure);
+        self.tonemap_shader.float("bloomStrength", self.bloom_strength);
+        unsafe {
+            self.gl.Disable(GL::DEPTH_TEST);
+            self.gl.ActiveTexture(GL::TEXTURE0);
+            self.gl.BindTexture(GL::TEXTURE_2D, self.color);
+            self.gl.ActiveTexture(GL::TEXTURE1);
+            self.gl.BindTexture(GL::TEXTURE_2D, self.bloom_textures[0]);
+            self.quad.draw();
+            self.gl.ActiveTexture(GL::TEXTURE0);
+            self.gl.Enable(GL::DEPTH_TEST);
+        }
+    }
+}
+
+impl Drop for HdrBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.DeleteTextures(1, &mut self.color);
+            self.gl.DeleteTextures(2, self.bloom_textures.as_mut_ptr());
+            self.gl.DeleteRenderbuffers(1, &mut self.rbo_depth);
+            self.gl.DeleteFramebuffers(1, &mut self.fbo);
+            self.gl.DeleteFramebuffers(2, self.bloom_fbos.as_mut_ptr());
+        }
+    }
+}
@@ -4,18 +4,23 @@ use shader::{Shader, Type as ShaderType};
 use std::collections::HashMap;
 use world::block::block::Block;
 use std::rc::Rc;
-use world::block::block_buffer::BlockBuffer;
+use render::block_buffer::BlockBuffer;
 use world::block::block_light::BlockLight;
 use util::math::Vec3;
 use camera::Camera;
-use world::block::block_texture::BlockTexture;
+use render::block_texture::BlockTexture;
 use world::block::block_type::BlockType;
 use world::block::block_database;
+use std::path::Path;
+use render::fog::{Fog, FogRegion};
+use render::ssao_buffer::{SsaoBuffer, SsaoQuality};
 
 pub struct Lighting {
     buffer: DeferredBuffer,
+    ssao: SsaoBuffer,
     item_shader: Shader,
     lights: HashMap<(i32, i32, i32), (BlockLight, BlockBuffer)>,
+    fog: Fog,
     gl: Gl
 }
 
@@ -31,7 +36,9 @@ impl Lighting {
         buffer.shader().int("gNormal", 1);
         buffer.shader().int("gAlbedoSpec", 2);
 
-        Lighting { buffer, gl: gl.clone(), item_shader, lights: HashMap::new() }
+        Lighting { buffer, gl: gl.clone(), item_shader, lights: HashMap::new(),
+            ssao: SsaoBuffer::new(gl, framebuffer_size, SsaoQuality::Medium),
+            fog: Fog::new(Vec3::new(0.0, 0.0, 0.0)) }
     }
 
     pub fn get_light_vec(&self) -> Vec<&BlockLight> {
@@ -46,6 +53,41 @@ impl Lighting {
         self.lights.insert((x, y, z), (block_light, block_buffer));
     }
 
+    pub fn reload_lights(&mut self) {
+        let database = block_database::get();
+        for (_, (light, buffer)) in self.lights.iter_mut() {
+            let block = database.get_block(light.block_type);
+            *light = block.get_light(&light.position);
+            *buffer = BlockBuffer::new(&self.gl, block);
+        }
+    }
+
+    pub fn set_fog_color(&mut self, color: &Vec3) {
+        self.fog.set_color(color.clone());
+    }
+
+    pub fn add_fog_region(&mut self, region: Box<dyn FogRegion>) {
+        self.fog.add_region(region);
+    }
+
+    pub fn set_render_distance(&mut self, render_distance: i32) {
+        self.fog.set_render_distance(render_distance);
+    }
+
+    pub fn ssao_enabled(&self) -> bool { self.ssao.is_enabled() }
+    pub fn ssao_quality(&self) -> SsaoQuality { self.ssao.quality() }
+
+    pub fn set_ssao(&mut self, enabled: bool, quality: SsaoQuality) {
+        self.ssao.set_enabled(enabled);
+        if self.ssao.quality() != quality {
+            self.ssao.set_quality(quality);
+        }
+    }
+
+    pub fn compute_occlusion(&self, camera: &Camera) {
+        self.ssao.compute(camera, self.buffer.position_texture(), self.buffer.normal_texture());
+    }
+
     pub fn bind_framebuffer(&self) {
         self.buffer.bind_framebuffer();
     }
@@ -57,11 +99,17 @@ impl Lighting {
     pub fn apply_lighting(&self, position: &Vec3) {
         self.buffer.apply_lighting(
             position,
-            &self.get_light_vec());
+            &self.get_light_vec(),
+            &self.fog.settings_at(position),
+            self.ssao.texture());
+    }
+
+    pub fn copy_depth_buffer(&self, target: u32) {
+        self.buffer.copy_depth_buffer(target);
     }
 
-    pub fn copy_depth_buffer(&self) {
-        self.buffer.copy_depth_buffer();
+    pub fn capture_g_buffer(&self, directory: &Path, name: &str) -> Result<(), String> {
+        self.buffer.capture_attachments(directory, name)
     }
 
     pub fn render_lighting(&self, camera: &Camera) {
@@ -1,2 +1,13 @@
 pub mod deferred_buffer;
-pub mod shadow_buffer;
\ No newline at end of file
+pub mod shadow_buffer;
+pub mod capture;
+pub mod screen_quad;
+pub mod hdr_buffer;
+pub mod ssao_buffer;
+pub mod chunk_buffer;
+pub mod block_texture;
+pub mod block_buffer;
+pub mod lighting;
+pub mod skybox;
+pub mod fog;
+pub mod world_renderer;
\ No newline at end of file
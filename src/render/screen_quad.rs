@@ -0,0 +1,55 @@
+use GL;
+use GL::Gl;
+use std::mem::size_of;
+use std::ptr::null as NULL;
+use std::ffi::c_void;
+
+const QUAD: [f32; 20] = [
+    -1.0, 1.0, 0.0, 0.0, 1.0,
+    -1.0, -1.0, 0.0, 0.0, 0.0,
+    1.0, 1.0, 0.0, 1.0, 1.0,
+    1.0, -1.0, 0.0, 1.0, 0.0
+];
+
+pub struct ScreenQuad {
+    gl: Gl,
+    vao: u32,
+    vbo: u32
+}
+
+impl ScreenQuad {
+    pub fn new(gl: &Gl) -> ScreenQuad {
+        let (mut vao, mut vbo) = (0, 0);
+        unsafe {
+            gl.GenVertexArrays(1, &mut vao);
+            gl.GenBuffers(1, &mut vbo);
+            gl.BindVertexArray(vao);
+            gl.BindBuffer(GL::ARRAY_BUFFER, vbo);
+            gl.BufferData(GL::ARRAY_BUFFER, (size_of::<f32>() * QUAD.len()) as isize, QUAD.as_ptr() as *const c_void, GL::STATIC_DRAW);
+            gl.EnableVertexAttribArray(0);
+            gl.VertexAttribPointer(0, 3, GL::FLOAT, GL::FALSE, (5 * size_of::<f32>()) as i32, NULL());
+            gl.EnableVertexAttribArray(1);
+            gl.VertexAttribPointer(1, 2, GL::FLOAT, GL::FALSE, (5 * size_of::<f32>()) as i32, (3 * size_of::<f32>()) as *const c_void);
+            gl.BindVertexArray(0);
+            gl.BindBuffer(GL::ARRAY_BUFFER, 0);
+        }
+        ScreenQuad { gl: gl.clone(), vao, vbo }
+    }
+
+    pub fn draw(&self) {
+        unsafe {
+            self.gl.BindVertexArray(self.vao);
+            self.gl.DrawArrays(GL::TRIANGLE_STRIP, 0, 4);
+            self.gl.BindVertexArray(0);
+        }
+    }
+}
+
+impl Drop for ScreenQuad {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.DeleteVertexArrays(1, &mut self.vao);
+            self.gl.DeleteBuffers(1, &mut self.vbo);
+        }
+    }
+}
@@ -2,41 +2,50 @@ use GL;
 use GL::Gl;
 use std::mem::size_of;
 use std::ffi::c_void;
-use stb_image::stb_image::bindgen::{stbi_load, stbi_image_free};
+use stb_image::stb_image::bindgen::{stbi_load_from_memory, stbi_image_free};
 use std::os::raw::{c_int, c_void as void};
-use std::ffi::CString;
+use util::resources;
 use shader::{Shader, Type as ShaderType};
+use math::{Vec3, vec3};
 
 pub struct SkyBox {
     vao: u32,
     vbo: u32,
     texture_id: u32,
     shader: Shader,
+    horizon_color: Vec3,
     gl: Gl
 }
 
 impl SkyBox {
     pub fn new(gl: &Gl) -> SkyBox {
         let (mut vao, mut vbo, mut texture_id) = (0, 0, 0);
+        let (mut horizon_sum, mut horizon_faces) = (vec3(0.0, 0.0, 0.0), 0);
         unsafe {
             let faces = vec![
-                CString::new("./assets/textures/sky/map_front.tga").unwrap(),
-                CString::new("./assets/textures/sky/map_back.tga").unwrap(),
-                CString::new("./assets/textures/sky/map_top.tga").unwrap(),
-                CString::new("./assets/textures/sky/map_bottom.tga").unwrap(),
-                CString::new("./assets/textures/sky/map_right.tga").unwrap(),
-                CString::new("./assets/textures/sky/map_left.tga").unwrap()
+                "assets/textures/sky/map_front.tga",
+                "assets/textures/sky/map_back.tga",
+                "assets/textures/sky/map_top.tga",
+                "assets/textures/sky/map_bottom.tga",
+                "assets/textures/sky/map_right.tga",
+                "assets/textures/sky/map_left.tga"
             ];
             gl.GenTextures(1, &mut texture_id);
             gl.BindTexture(GL::TEXTURE_CUBE_MAP, texture_id);
 
             let (mut width, mut height, mut channels) = (0, 0, 0);
             for (i, face) in faces.iter().enumerate() {
-                let data = stbi_load(face.as_ptr(), &mut width, &mut height, &mut channels, 0);
+                let bytes = resources::get().read(face).unwrap_or_else(|e| panic!("Failed to load cubemap: {}", e));
+                let data = stbi_load_from_memory(bytes.as_ptr(), bytes.len() as c_int, &mut width, &mut height, &mut channels, 0);
                 if !data.is_null() {
                     gl.TexImage2D(GL::TEXTURE_CUBE_MAP_POSITIVE_X + i as u32, 0, GL::RGB as i32, width, height, 0, GL::RGB, GL::UNSIGNED_BYTE, data as *const c_void);
+                    if i != 2 && i != 3 {
+                        let pixels = ::std::slice::from_raw_parts(data as *const u8, (width * height * channels) as usize);
+                        horizon_sum = horizon_sum + SkyBox::average_row(pixels, width, height / 2, channels);
+                        horizon_faces += 1;
+                    }
                 } else {
-                    panic!("Failed to load cubemap: {}", face.to_str().unwrap());
+                    panic!("Failed to load cubemap: {}", face);
                 }
                 stbi_image_free(data as *mut void);
             }
@@ -57,9 +66,22 @@ impl SkyBox {
 
         SkyBox { vao, vbo, texture_id,
             shader: Shader::new(gl, ShaderType::CubeMap, false).unwrap(),
+            horizon_color: horizon_sum / (horizon_faces.max(1) as f32),
             gl: gl.clone() }
     }
 
+    fn average_row(pixels: &[u8], width: i32, row: i32, channels: i32) -> Vec3 {
+        let start = (row * width * channels) as usize;
+        let end = start + (width * channels) as usize;
+        let mut sum = vec3(0.0, 0.0, 0.0);
+        for pixel in pixels[start..end].chunks(channels as usize) {
+            sum = sum + vec3(pixel[0] as f32, pixel[1] as f32, pixel[2] as f32);
+        }
+        sum / (width as f32 * 255.0)
+    }
+
+    pub fn horizon_color(&self) -> &Vec3 { &self.horizon_color }
+
     pub fn draw(&self, view: &::math::Mat4, projection: &::math::Mat4) {
         use math::{Mat4, vec4};
         unsafe {
@@ -0,0 +1,198 @@
+use GL;
+use GL::Gl;
+use rand::{thread_rng, Rng};
+use shader::{Shader, Type as ShaderType};
+use math::{Vec3, vec3, Geom::normalize};
+use camera::Camera;
+use render::screen_quad::ScreenQuad;
+use std::ffi::c_void;
+
+const NOISE_SIZE: i32 = 4;
+const RADIUS: f32 = 0.5;
+const BIAS: f32 = 0.025;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum SsaoQuality {
+    Low,
+    Medium,
+    High
+}
+
+impl SsaoQuality {
+    pub fn kernel_size(&self) -> usize {
+        match *self {
+            SsaoQuality::Low => 8,
+            SsaoQuality::Medium => 16,
+            SsaoQuality::High => 32
+        }
+    }
+
+    pub fn next(&self) -> SsaoQuality {
+        match *self {
+            SsaoQuality::Low => SsaoQuality::Medium,
+            SsaoQuality::Medium => SsaoQuality::High,
+            SsaoQuality::High => SsaoQuality::Low
+        }
+    }
+}
+
+pub struct SsaoBuffer {
+    gl: Gl,
+    fbo: u32,
+    blur_fbo: u32,
+    occlusion: u32,
+    blurred: u32,
+    noise: u32,
+    quad: ScreenQuad,
+    shader: Shader,
+    blur_shader: Shader,
+    quality: SsaoQuality,
+    enabled: bool
+}
+
+impl SsaoBuffer {
+    pub fn new(gl: &Gl, framebuffer_size: (i32, i32), quality: SsaoQuality) -> SsaoBuffer {
+        let (width, height) = framebuffer_size;
+        let (mut fbo, mut blur_fbo, mut occlusion, mut blurred, mut noise) = (0, 0, 0, 0, 0);
+
+        let mut rng = thread_rng();
+        let noise_data: Vec<f32> = (0..NOISE_SIZE * NOISE_SIZE)
+            .flat_map(|_| vec![rng.gen::<f32>() * 2.0 - 1.0, rng.gen::<f32>() * 2.0 - 1.0, 0.0])
+            .collect();
+
+        unsafe {
+            gl.GenFramebuffers(1, &mut fbo);
+            gl.BindFramebuffer(GL::FRAMEBUFFER, fbo);
+            gl.GenTextures(1, &mut occlusion);
+            gl.BindTexture(GL::TEXTURE_2D, occlusion);
+            gl.TexImage2D(GL::TEXTURE_2D, 0, GL::R16F as i32, width, height, 0, GL::RED, GL::FLOAT, ::std::ptr::null());
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::NEAREST as i32);
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+            gl.FramebufferTexture2D(GL::FRAMEBUFFER, GL::COLOR_ATTACHMENT0, GL::TEXTURE_2D, occlusion, 0);
+            if gl.CheckFramebufferStatus(GL::FRAMEBUFFER) != GL::FRAMEBUFFER_COMPLETE {
+                println!("SSAO framebuffer not complete!");
+            }
+
+            gl.GenFramebuffers(1, &mut blur_fbo);
+            gl.BindFramebuffer(GL::FRAMEBUFFER, blur_fbo);
+            gl.GenTextures(1, &mut blurred);
+            gl.BindTexture(GL::TEXTURE_2D, blurred);
+            gl.TexImage2D(GL::TEXTURE_2D, 0, GL::R16F as i32, width, height, 0, GL::RED, GL::FLOAT, ::std::ptr::null());
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::NEAREST as i32);
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
+            gl.FramebufferTexture2D(GL::FRAMEBUFFER, GL::COLOR_ATTACHMENT0, GL::TEXTURE_2D, blurred, 0);
+            if gl.CheckFramebufferStatus(GL::FRAMEBUFFER) != GL::FRAMEBUFFER_COMPLETE {
+                println!("SSAO blur framebuffer not complete!");
+            }
+
+            gl.GenTextures(1, &mut noise);
+            gl.BindTexture(GL::TEXTURE_2D, noise);
+            gl.TexImage2D(GL::TEXTURE_2D, 0, GL::RGB16F as i32, NOISE_SIZE, NOISE_SIZE, 0, GL::RGB, GL::FLOAT, noise_data.as_ptr() as *const c_void);
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::NEAREST as i32);
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::REPEAT as i32);
+            gl.TexParameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::REPEAT as i32);
+
+            gl.BindTexture(GL::TEXTURE_2D, 0);
+            gl.BindFramebuffer(GL::FRAMEBUFFER, 0);
+        }
+
+        let shader = Shader::new(gl, ShaderType::Ssao, false).unwrap();
+        shader.bind();
+        shader.int("gPosition", 0);
+        shader.int("gNormal", 1);
+        shader.int("noise", 2);
+        shader.float("radius", RADIUS);
+        shader.float("bias", BIAS);
+        shader.vec2("noiseScale", &vector!(width as f32 / NOISE_SIZE as f32, height as f32 / NOISE_SIZE as f32));
+
+        let blur_shader = Shader::new(gl, ShaderType::SsaoBlur, false).unwrap();
+        blur_shader.bind();
+        blur_shader.int("occlusion", 0);
+
+        let mut buffer = SsaoBuffer {
+            gl: gl.clone(), fbo, blur_fbo, occlusion, blurred, noise,
+            quad: ScreenQuad::new(gl), shader, blur_shader,
+            quality, enabled: true
+        };
+        buffer.set_quality(quality);
+        buffer
+    }
+
+    pub fn is_enabled(&self) -> bool { self.enabled }
+    pub fn set_enabled(&mut self, enabled: bool) { self.enabled = enabled; }
+
+    pub fn quality(&self) -> SsaoQuality { self.quality }
+    pub fn set_quality(&mut self, quality: SsaoQuality) {
+        self.quality = quality;
+        let kernel = SsaoBuffer::generate_kernel(quality.kernel_size());
+        self.shader.bind();
+        self.shader.int("kernelSize", kernel.len() as i32);
+        for (i, sample) in kernel.iter().enumerate() {
+            self.shader.vec3(&format!("samples[{}]", i), sample);
+        }
+    }
+
+    pub fn texture(&self) -> Option<u32> {
+        match self.enabled {
+            true => Some(self.blurred),
+            false => None
+        }
+    }
+
+    fn generate_kernel(size: usize) -> Vec<Vec3> {
+        let mut rng = thread_rng();
+        (0..size).map(|i| {
+            let sample = normalize(vec3(
+                rng.gen::<f32>() * 2.0 - 1.0,
+                rng.gen::<f32>() * 2.0 - 1.0,
+                rng.gen::<f32>()
+            )) * rng.gen::<f32>();
+            let scale = i as f32 / size as f32;
+            sample * (0.1 + 0.9 * scale * scale)
+        }).collect()
+    }
+
+    pub fn compute(&self, camera: &Camera, g_position: u32, g_normal: u32) {
+        if !self.enabled { return; }
+        unsafe {
+            self.gl.Disable(GL::DEPTH_TEST);
+            self.gl.BindFramebuffer(GL::FRAMEBUFFER, self.fbo);
+            self.gl.Clear(GL::COLOR_BUFFER_BIT);
+            self.shader.bind();
+            self.shader.mat_4("view", camera.view());
+            self.shader.mat_4("projection", camera.projection());
+            self.gl.ActiveTexture(GL::TEXTURE0);
+            self.gl.BindTexture(GL::TEXTURE_2D, g_position);
+            self.gl.ActiveTexture(GL::TEXTURE1);
+            self.gl.BindTexture(GL::TEXTURE_2D, g_normal);
+            self.gl.ActiveTexture(GL::TEXTURE2);
+            self.gl.BindTexture(GL::TEXTURE_2D, self.noise);
+            self.quad.draw();
+
+            self.gl.BindFramebuffer(GL::FRAMEBUFFER, self.blur_fbo);
+            self.gl.Clear(GL::COLOR_BUFFER_BIT);
+            self.blur_shader.bind();
+            self.gl.ActiveTexture(GL::TEXTURE0);
+            self.gl.BindTexture(GL::TEXTURE_2D, self.occlusion);
+            self.quad.draw();
+
+            self.gl.BindFramebuffer(GL::FRAMEBUFFER, 0);
+            self.gl.Enable(GL::DEPTH_TEST);
+        }
+    }
+}
+
+impl Drop for SsaoBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.DeleteTextures(1, &mut self.occlusion);
+            self.gl.DeleteTextures(1, &mut self.blurred);
+            self.gl.DeleteTextures(1, &mut self.noise);
+            self.gl.DeleteFramebuffers(1, &mut self.fbo);
+            self.gl.DeleteFramebuffers(1, &mut self.blur_fbo);
+        }
+    }
+}
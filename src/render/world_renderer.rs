@@ -0,0 +1,124 @@
+use GL::Gl;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use threadpool::ThreadPool;
+use camera::Camera;
+use render::block_texture::BlockTexture;
+use render::chunk_buffer::ChunkBuffer;
+use shader::Shader;
+use util::math::Vec3;
+use util::timer::Timer;
+use world::chunk::chunk_lod::LodLevel;
+use world::chunk::chunk_mesh::ChunkMesh;
+use world::world::World;
+
+struct MeshResult {
+    job: u64,
+    index: (i32, i32),
+    lod: LodLevel,
+    mesh: ChunkMesh
+}
+
+pub struct WorldRenderer {
+    gl: Gl,
+    block_texture: BlockTexture,
+    buffers: HashMap<(i32, i32), (LodLevel, ChunkBuffer)>,
+    pending: HashMap<(i32, i32), (u64, LodLevel)>,
+    mesh_queue: Arc<Mutex<Vec<MeshResult>>>,
+    thread_pool: ThreadPool,
+    next_job: u64
+}
+
+impl WorldRenderer {
+    pub fn new(gl: &Gl, threads: usize) -> WorldRenderer {
+        WorldRenderer {
+            gl: gl.clone(),
+            block_texture: BlockTexture::new(gl),
+            buffers: HashMap::new(),
+            pending: HashMap::new(),
+            mesh_queue: Arc::new(Mutex::new(Vec::new())),
+            thread_pool: ThreadPool::new(threads),
+            next_job: 0
+        }
+    }
+
+    pub fn sync(&mut self, world: &mut World, position: &Vec3) {
+        let center = World::chunk_coordinates(position);
+        for index in world.take_changed_chunks() {
+            self.mesh(world, index, LodLevel::for_distance(World::chunk_distance(center, index)));
+        }
+        let outdated: Vec<((i32, i32), LodLevel)> = world.chunk_indices()
+            .map(|&index| (index, LodLevel::for_distance(World::chunk_distance(center, index))))
+            .filter(|&(index, lod)| match self.pending.get(&index) {
+                Some(&(_, pending)) => pending != lod,
+                None => self.buffers.get(&index).map_or(true, |&(meshed, _)| meshed != lod)
+            })
+            .collect();
+        for (index, lod) in outdated {
+            self.mesh(world, index, lod);
+        }
+
+        let results: Vec<MeshResult> = self.mesh_queue.lock().unwrap().drain(..).collect();
+        for result in results {
+            if self.pending.get(&result.index).map(|&(job, _)| job) != Some(result.job) { continue; }
+            self.pending.remove(&result.index);
+            let buffer = ChunkBuffer::new(&self.gl, result.mesh.verts(), result.mesh.inds());
+            self.buffers.insert(result.index, (result.lod, buffer));
+        }
+        self.buffers.retain(|index, _| world.chunk(index).is_some());
+        self.pending.retain(|index, _| world.chunk(index).is_some());
+    }
+
+    pub fn remesh(&mut self, world: &World) {
+        let indices: Vec<((i32, i32), LodLevel)> = world.chunk_indices()
+            .map(|&index| (index, self.buffers.get(&index).map_or(LodLevel::Full, |&(lod, _)| lod)))
+            .collect();
+        for (index, lod) in indices {
+            self.mesh(world, index, lod);
+        }
+    }
+
+    pub fn is_meshing(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    fn mesh(&mut self, world: &World, index: (i32, i32), lod: LodLevel) {
+        let blocks = match world.chunk(&index) {
+            Some(chunk) => chunk.blocks(),
+            None => return
+        };
+        self.next_job += 1;
+        let job = self.next_job;
+        self.pending.insert(index, (job, lod));
+        let queue = self.mesh_queue.clone();
+        self.thread_pool.execute(move|| {
+            let mesh = ChunkMesh::new(&blocks, lod);
+            queue.lock().unwrap().push(MeshResult { job, index, lod, mesh });
+        });
+    }
+
+    pub fn render(&self, world: &World, block_shader: &Shader, camera: &Camera) {
+        self.bind_block_texture(0);
+        block_shader.bind();
+        block_shader.mat_4("view", camera.view());
+        block_shader.mat_4("projection", camera.projection());
+        block_shader.int("tex", 0);
+        for (index, &(_, ref buffer)) in self.buffers.iter() {
+            if let Some(chunk) = world.chunk(index) {
+                buffer.draw(block_shader, &chunk.model());
+            }
+        }
+    }
+
+    pub fn reload_textures(&mut self) {
+        self.block_texture = BlockTexture::new(&self.gl);
+    }
+
+    pub fn animate_textures(&mut self, timer: &Timer) {
+        self.block_texture.animate(timer.elapsed());
+    }
+
+    pub fn bind_block_texture(&self, loc: u32) {
+        self.block_texture.bind(loc);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,89 @@
+use libc;
+use std::io::{self, BufRead};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+pub const HELP: &'static str = "Commands: help, stop, save, list, seed, status, say <message>";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Help,
+    Stop,
+    Save,
+    List,
+    Seed,
+    Status,
+    Say(String)
+}
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Command, String> {
+        let line = line.trim();
+        let (name, argument) = match line.find(char::is_whitespace) {
+            Some(i) => (&line[..i], line[i..].trim()),
+            None => (line, "")
+        };
+        match name {
+            "help" | "?" => Ok(Command::Help),
+            "stop" => Ok(Command::Stop),
+            "save" => Ok(Command::Save),
+            "list" => Ok(Command::List),
+            "seed" => Ok(Command::Seed),
+            "status" => Ok(Command::Status),
+            "say" if !argument.is_empty() => Ok(Command::Say(argument.to_string())),
+            "say" => Err("Usage: say <message>".to_string()),
+            _ => Err(format!("Unknown command {}. {}", name, HELP))
+        }
+    }
+}
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn interrupt(_: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+pub struct Console {
+    lines: Receiver<String>
+}
+
+impl Console {
+    pub fn start() -> Console {
+        unsafe {
+            libc::signal(libc::SIGINT, interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t);
+            libc::signal(libc::SIGTERM, interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t);
+        }
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let stdin = io::stdin();
+            for line in stdin.lock().lines() {
+                match line {
+                    Ok(line) => if tx.send(line).is_err() { break },
+                    Err(_) => break
+                }
+            }
+        });
+        Console { lines: rx }
+    }
+
+    pub fn poll(&self) -> Vec<Result<Command, String>> {
+        let mut commands = Vec::new();
+        if INTERRUPTED.swap(false, Ordering::SeqCst) {
+            println!("Interrupted");
+            commands.push(Ok(Command::Stop));
+        }
+        loop {
+            match self.lines.try_recv() {
+                Ok(ref line) if line.trim().is_empty() => continue,
+                Ok(line) => commands.push(Command::parse(&line)),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    commands.push(Ok(Command::Stop));
+                    break;
+                }
+            }
+        }
+        commands
+    }
+}
\ No newline at end of file
@@ -0,0 +1,4 @@
+pub mod properties;
+pub mod console;
+pub mod player;
+pub mod server;
\ No newline at end of file
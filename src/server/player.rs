@@ -0,0 +1,68 @@
+use math::Vec3;
+use net::connection::Connection;
+use net::protocol::Packet;
+use world::block::block_id_map::BlockIdMap;
+use std::collections::HashSet;
+
+pub struct ServerPlayer {
+    pub connection: Connection,
+    pub name: Option<String>,
+    pub handshake: bool,
+    pub position: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub last_move_tick: u64,
+    pub sent_chunks: HashSet<(i32, i32)>,
+    pub known_ids: usize,
+    pub disconnect: Option<String>
+}
+
+impl ServerPlayer {
+    pub fn new(connection: Connection) -> ServerPlayer {
+        ServerPlayer {
+            connection,
+            name: None,
+            handshake: false,
+            position: Vec3::new(0.0, 0.0, 0.0),
+            yaw: 0.0,
+            pitch: 0.0,
+            last_move_tick: 0,
+            sent_chunks: HashSet::new(),
+            known_ids: 0,
+            disconnect: None
+        }
+    }
+
+    pub fn is_logged_in(&self) -> bool {
+        self.name.is_some() && self.disconnect.is_none()
+    }
+
+    pub fn display_name(&self) -> String {
+        match self.name {
+            Some(ref name) => name.clone(),
+            None => self.connection.peer().to_string()
+        }
+    }
+
+    pub fn send(&mut self, packet: &Packet) {
+        if self.disconnect.is_some() { return; }
+        if let Err(e) = self.connection.send(packet) {
+            self.disconnect = Some(e);
+        }
+    }
+
+    pub fn sync_ids(&mut self, ids: &BlockIdMap) {
+        if ids.keys().len() > self.known_ids {
+            self.known_ids = ids.keys().len();
+            self.send(&Packet::BlockIds { keys: ids.keys().to_vec() });
+        }
+    }
+
+    pub fn kick(&mut self, reason: &str) {
+        self.send(&Packet::Disconnect { reason: reason.to_string() });
+        let _ = self.connection.flush();
+        if self.disconnect.is_none() {
+            self.disconnect = Some(reason.to_string());
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,95 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use util::noise::DEFAULT_SEED;
+use world::constants::{RENDER_DISTANCE, MIN_RENDER_DISTANCE, MAX_RENDER_DISTANCE};
+
+#[derive(Debug, Clone)]
+pub struct ServerProperties {
+    pub world: PathBuf,
+    pub seed: u32,
+    pub view_distance: i32,
+    pub threads: usize,
+    pub autosave_interval: u64,
+    pub port: u16,
+    pub motd: String,
+    pub max_players: usize
+}
+
+impl Default for ServerProperties {
+    fn default() -> Self {
+        ServerProperties {
+            world: PathBuf::from("world"),
+            seed: DEFAULT_SEED,
+            view_distance: RENDER_DISTANCE,
+            threads: 2,
+            autosave_interval: 300,
+            port: 25565,
+            motd: "A Minecraft server".to_string(),
+            max_players: 8
+        }
+    }
+}
+
+impl ServerProperties {
+    pub fn load(path: &Path) -> Result<ServerProperties, String> {
+        let mut properties = ServerProperties::default();
+        if !path.exists() {
+            println!("Creating default server properties at {}", path.display());
+            properties.save(path)?;
+            return Ok(properties);
+        }
+
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read server properties {}: {}", path.display(), e))?;
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+            let (key, value) = match line.find('=') {
+                Some(i) => (line[..i].trim(), line[i + 1..].trim()),
+                None => return Err(format!("{}:{}: expected key=value", path.display(), number + 1))
+            };
+            properties.set(key, value).map_err(|e| format!("{}:{}: {}", path.display(), number + 1, e))?;
+        }
+
+        if properties.view_distance < MIN_RENDER_DISTANCE || properties.view_distance > MAX_RENDER_DISTANCE {
+            let clamped = properties.view_distance.max(MIN_RENDER_DISTANCE).min(MAX_RENDER_DISTANCE);
+            println!("view-distance must be between {} and {}, using {}", MIN_RENDER_DISTANCE, MAX_RENDER_DISTANCE, clamped);
+            properties.view_distance = clamped;
+        }
+        if properties.threads == 0 {
+            return Err("threads must be at least 1".to_string());
+        }
+        Ok(properties)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let mut file = File::create(path)
+            .map_err(|e| format!("Failed to create server properties {}: {}", path.display(), e))?;
+        write!(file, "# Minecraft server properties\n\
+            world={}\nseed={}\nview-distance={}\nthreads={}\nautosave-interval={}\nport={}\nmotd={}\nmax-players={}\n",
+            self.world.display(), self.seed, self.view_distance, self.threads, self.autosave_interval,
+            self.port, self.motd, self.max_players)
+            .map_err(|e| format!("Failed to write server properties {}: {}", path.display(), e))
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "world" => self.world = PathBuf::from(value),
+            "seed" => self.seed = parse(key, value)?,
+            "view-distance" => self.view_distance = parse(key, value)?,
+            "threads" => self.threads = parse(key, value)?,
+            "autosave-interval" => self.autosave_interval = parse(key, value)?,
+            "port" => self.port = parse(key, value)?,
+            "motd" => self.motd = value.to_string(),
+            "max-players" => self.max_players = parse(key, value)?,
+            _ => println!("Ignoring unknown server property {}", key)
+        }
+        Ok(())
+    }
+}
+
+fn parse<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("Invalid value for {}: {}", key, value))
+}
\ No newline at end of file
@@ -0,0 +1,460 @@
+use glm::{distance, Vec3};
+use math::Vec3i;
+use net::connection::Connection;
+use net::protocol::{Packet, MAX_CHAT_LENGTH, PROTOCOL_VERSION};
+use server::console::{Command, Console, HELP};
+use server::player::ServerPlayer;
+use server::properties::ServerProperties;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::TcpListener;
+use std::thread;
+use std::time::{Duration, Instant};
+use util::noise;
+use util::timer::{Timer, TICK_RATE};
+use world::block::block_database;
+use world::block::block_state::BlockState;
+use world::chunk::chunk_codec::{self, Compression};
+use world::save::{LevelData, WorldSave};
+use world::world::World;
+
+const CHUNKS_PER_TICK: usize = 4;
+const MAX_MOVE_PER_TICK: f32 = 0.5;
+const MAX_REACH: f32 = 8.0;
+const MAX_NAME_LENGTH: usize = 16;
+const SPAWN_TIMEOUT: u64 = 60;
+
+pub struct Server {
+    properties: ServerProperties,
+    world: World,
+    save: WorldSave,
+    level: LevelData,
+    console: Console,
+    listener: TcpListener,
+    players: HashMap<u32, ServerPlayer>,
+    next_player_id: u32,
+    last_save: Instant,
+    running: bool
+}
+
+impl Server {
+    pub fn start(properties: ServerProperties) -> Result<Server, String> {
+        let save = WorldSave::open(&properties.world)?;
+        let level = match save.load_level()? {
+            Some(level) => level,
+            None => LevelData { seed: properties.seed, spawn: None, ticks: 0 }
+        };
+        if level.seed != properties.seed {
+            println!("Using seed {} from {} instead of {}", level.seed, properties.world.display(), properties.seed);
+        }
+        noise::set_seed(level.seed);
+
+        let listener = TcpListener::bind(("0.0.0.0", properties.port))
+            .map_err(|e| format!("Failed to listen on port {}: {}", properties.port, e))?;
+        listener.set_nonblocking(true).map_err(|e| format!("Failed to configure listener: {}", e))?;
+
+        let mut world = World::new(properties.view_distance, properties.threads);
+        let chunks = save.load_chunks()?;
+        println!("Loaded {} modified chunks from {}", chunks.len(), properties.world.display());
+        world.set_modified_chunks(chunks);
+        world.initialize_chunks();
+
+        let mut server = Server {
+            properties,
+            world,
+            save,
+            level,
+            console: Console::start(),
+            listener,
+            players: HashMap::new(),
+            next_player_id: 1,
+            last_save: Instant::now(),
+            running: true
+        };
+        let started = Instant::now();
+        while server.level.spawn.is_none() {
+            if server.world.failed_jobs() > 0 {
+                return Err("Chunk generation failed while finding the spawn point".to_string());
+            }
+            if started.elapsed() >= Duration::from_secs(SPAWN_TIMEOUT) {
+                return Err(format!("Timed out after {}s waiting for the spawn chunk", SPAWN_TIMEOUT));
+            }
+            server.world.take_chunk_from_queue();
+            if let Some(spawn) = *server.world.get_player_spawn() {
+                server.level.spawn = Some([spawn.x, spawn.y, spawn.z]);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        Ok(server)
+    }
+
+    pub fn run(&mut self) {
+        println!("Server started on port {} with world {} and seed {}. {}", self.port(),
+                 self.properties.world.display(), self.level.seed, HELP);
+        let mut timer = Timer::new();
+        while self.running {
+            for _ in 0..timer.tick() {
+                self.tick();
+            }
+            for command in self.console.poll() {
+                match command {
+                    Ok(command) => self.execute(command),
+                    Err(e) => println!("{}", e)
+                }
+            }
+            if self.properties.autosave_interval > 0
+                && self.last_save.elapsed() >= Duration::from_secs(self.properties.autosave_interval) {
+                self.save();
+            }
+            if self.running {
+                thread::sleep(timer.time_to_next_tick());
+            }
+        }
+        println!("Stopping server");
+        for player in self.players.values_mut() {
+            player.kick("Server closed");
+        }
+        self.save();
+    }
+
+    pub fn port(&self) -> u16 {
+        self.listener.local_addr().map(|a| a.port()).unwrap_or(self.properties.port)
+    }
+
+    fn spawn(&self) -> Vec3 {
+        let spawn = self.level.spawn.unwrap_or([0.0, 0.0, 0.0]);
+        Vec3::new(spawn[0], spawn[1], spawn[2])
+    }
+
+    fn tick(&mut self) {
+        self.level.ticks += 1;
+        self.accept_connections();
+
+        let mut incoming = Vec::new();
+        for (&id, player) in self.players.iter_mut() {
+            if player.disconnect.is_some() { continue; }
+            match player.connection.receive() {
+                Ok(packets) => incoming.push((id, packets)),
+                Err(e) => player.disconnect = Some(e)
+            }
+        }
+        for (id, packets) in incoming {
+            for packet in packets {
+                self.handle_packet(id, packet);
+            }
+        }
+
+        self.world.take_chunk_from_queue();
+        self.world.take_changed_chunks();
+        let mut centers: Vec<Vec3> = self.players.values().filter(|p| p.is_logged_in()).map(|p| p.position).collect();
+        centers.push(self.spawn());
+        self.world.build_chunks_around(&centers);
+
+        self.stream_chunks();
+        self.remove_disconnected();
+    }
+
+    fn accept_connections(&mut self) {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => match Connection::new(stream) {
+                    Ok(connection) => {
+                        let id = self.next_player_id;
+                        self.next_player_id += 1;
+                        self.players.insert(id, ServerPlayer::new(connection));
+                    },
+                    Err(e) => println!("{}", e)
+                },
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    println!("Failed to accept connection: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn handle_packet(&mut self, id: u32, packet: Packet) {
+        let (handshake, logged_in) = match self.players.get(&id) {
+            Some(player) if player.disconnect.is_none() => (player.handshake, player.is_logged_in()),
+            _ => return
+        };
+        match packet {
+            Packet::Handshake { version } if !handshake => {
+                if version == PROTOCOL_VERSION {
+                    self.player(id).handshake = true;
+                } else {
+                    let reason = format!("Server uses protocol version {}, client uses {}", PROTOCOL_VERSION, version);
+                    self.player(id).kick(&reason);
+                }
+            },
+            Packet::Login { name } if handshake && !logged_in => self.login(id, name),
+            Packet::PlayerPosition { position, yaw, pitch, .. } if logged_in => self.move_player(id, position, yaw, pitch),
+            Packet::BlockChange { position, id: block_id, properties } if logged_in => {
+                self.change_block(id, position, block_id, properties)
+            },
+            Packet::Chat { message } if logged_in => {
+                let message: String = message.trim().chars().take(MAX_CHAT_LENGTH).collect();
+                if !message.is_empty() {
+                    let name = self.player(id).display_name();
+                    self.broadcast_chat(&format!("<{}> {}", name, message));
+                }
+            },
+            packet => {
+                let reason = format!("Unexpected packet {:?}", packet);
+                self.player(id).kick(&reason);
+            }
+        }
+    }
+
+    fn login(&mut self, id: u32, name: String) {
+        let online = self.players.values().filter(|p| p.is_logged_in()).count();
+        let taken = self.players.values().any(|p| p.name.as_ref() == Some(&name));
+        let valid = !name.is_empty() && name.len() <= MAX_NAME_LENGTH
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        let refusal = if !valid {
+            Some(format!("Names must be 1 to {} letters, digits or underscores", MAX_NAME_LENGTH))
+        } else if taken {
+            Some(format!("{} is already playing", name))
+        } else if online >= self.properties.max_players {
+            Some("Server is full".to_string())
+        } else {
+            None
+        };
+        if let Some(reason) = refusal {
+            self.player(id).kick(&reason);
+            return;
+        }
+
+        let (spawn, ticks, view_distance) = (self.spawn(), self.level.ticks, self.properties.view_distance);
+        let others: Vec<Packet> = self.players.iter().filter(|&(_, p)| p.is_logged_in())
+            .map(|(&player_id, p)| Packet::PlayerPosition { player_id, position: p.position, yaw: p.yaw, pitch: p.pitch })
+            .collect();
+        {
+            let player = self.player(id);
+            player.name = Some(name.clone());
+            player.position = spawn;
+            player.last_move_tick = ticks;
+            player.send(&Packet::LoginSuccess { player_id: id, position: spawn, view_distance });
+            for packet in others.iter() {
+                player.send(packet);
+            }
+        }
+        let motd = self.properties.motd.clone();
+        self.player(id).send(&Packet::Chat { message: motd });
+        self.broadcast_chat(&format!("{} joined the game", name));
+    }
+
+    fn move_player(&mut self, id: u32, position: Vec3, yaw: f32, pitch: f32) {
+        let ticks = self.level.ticks;
+        let packet = {
+            let player = self.player(id);
+            let elapsed = (ticks - player.last_move_tick).max(1) as f32;
+            let finite = position.x.is_finite() && position.y.is_finite() && position.z.is_finite();
+            if !finite || distance(position, player.position) > MAX_MOVE_PER_TICK * elapsed {
+                let correction = Packet::PlayerPosition { player_id: id, position: player.position, yaw, pitch };
+                player.send(&correction);
+                return;
+            }
+            player.position = position;
+            player.yaw = yaw;
+            player.pitch = pitch;
+            player.last_move_tick = ticks;
+            Packet::PlayerPosition { player_id: id, position, yaw, pitch }
+        };
+        self.broadcast(&packet, Some(id));
+    }
+
+    fn change_block(&mut self, id: u32, position: Vec3i, block_id: u16, properties: [u8; 3]) {
+        let state = self.save.ids().state(block_id, properties, &block_database::get());
+        if let Err(ref e) = state {
+            println!("Rejected block change from {}: {}", self.player(id).display_name(), e);
+        }
+        let eye = self.player(id).position;
+        let center = Vec3::new(position.x as f32, position.y as f32, position.z as f32);
+        match state {
+            Ok(state) if distance(eye, center) <= MAX_REACH && self.world.set_block(&position, state) => {
+                let packet = self.block_change(position, state);
+                self.broadcast(&packet, None);
+            },
+            _ => {
+                let current = self.world.block_at(&position);
+                let packet = self.block_change(position, current);
+                self.player(id).send(&packet);
+            }
+        }
+    }
+
+    fn block_change(&mut self, position: Vec3i, state: BlockState) -> Packet {
+        let ids = self.save.ids_mut();
+        let id = ids.saved_id(state.block_type, &block_database::get());
+        for player in self.players.values_mut().filter(|p| p.is_logged_in()) {
+            player.sync_ids(ids);
+        }
+        Packet::BlockChange { position, id, properties: state.properties() }
+    }
+
+    fn stream_chunks(&mut self) {
+        let view_distance = self.properties.view_distance;
+        let world = &self.world;
+        let ids = self.save.ids_mut();
+        for player in self.players.values_mut().filter(|p| p.is_logged_in()) {
+            let center = World::chunk_coordinates(&player.position);
+            let unload: Vec<(i32, i32)> = player.sent_chunks.iter().cloned()
+                .filter(|&index| World::chunk_distance(center, index) > view_distance + 1)
+                .collect();
+            for (x, z) in unload {
+                player.sent_chunks.remove(&(x, z));
+                player.send(&Packet::UnloadChunk { x, z });
+            }
+
+            let mut missing = Vec::new();
+            for x in (center.0 - view_distance)..=(center.0 + view_distance) {
+                for z in (center.1 - view_distance)..=(center.1 + view_distance) {
+                    if !player.sent_chunks.contains(&(x, z)) && world.chunk(&(x, z)).is_some() {
+                        missing.push((x, z));
+                    }
+                }
+            }
+            missing.sort_by_key(|&index| World::chunk_distance(center, index));
+            for (x, z) in missing.into_iter().take(CHUNKS_PER_TICK) {
+                let data = chunk_codec::encode(&world.chunk(&(x, z)).unwrap().blocks(), ids, Compression::Zlib);
+                player.sync_ids(ids);
+                player.sent_chunks.insert((x, z));
+                player.send(&Packet::ChunkData { x, z, data });
+            }
+            if let Err(e) = player.connection.flush() {
+                player.disconnect = Some(e);
+            }
+        }
+    }
+
+    fn remove_disconnected(&mut self) {
+        let left: Vec<u32> = self.players.iter().filter(|&(_, p)| p.disconnect.is_some()).map(|(&id, _)| id).collect();
+        for id in left {
+            let player = self.players.remove(&id).unwrap();
+            println!("{} disconnected: {}", player.display_name(), player.disconnect.unwrap_or_default());
+            if let Some(name) = player.name {
+                self.broadcast(&Packet::PlayerLeft { player_id: id }, None);
+                self.broadcast_chat(&format!("{} left the game", name));
+            }
+        }
+    }
+
+    fn player(&mut self, id: u32) -> &mut ServerPlayer {
+        self.players.get_mut(&id).unwrap()
+    }
+
+    fn broadcast(&mut self, packet: &Packet, except: Option<u32>) {
+        for (&id, player) in self.players.iter_mut() {
+            if player.is_logged_in() && Some(id) != except {
+                player.send(packet);
+            }
+        }
+    }
+
+    fn broadcast_chat(&mut self, message: &str) {
+        println!("{}", message);
+        self.broadcast(&Packet::Chat { message: message.to_string() }, None);
+    }
+
+    fn execute(&mut self, command: Command) {
+        match command {
+            Command::Help => println!("{}", HELP),
+            Command::Stop => self.running = false,
+            Command::Save => self.save(),
+            Command::List => {
+                let mut names: Vec<String> = self.players.values().filter_map(|p| p.name.clone()).collect();
+                names.sort();
+                println!("{}/{} players online: {}", names.len(), self.properties.max_players, names.join(", "));
+            },
+            Command::Seed => println!("Seed: {}", self.level.seed),
+            Command::Status => println!("World time {}s, {} modified chunks, {} connections",
+                self.level.ticks / TICK_RATE as u64, self.world.modified_chunks().len(), self.players.len()),
+            Command::Say(message) => self.broadcast_chat(&format!("[Server] {}", message))
+        }
+    }
+
+    fn save(&mut self) {
+        let stopwatch = Instant::now();
+        match self.save.save(&self.level, self.world.modified_chunks()) {
+            Ok(()) => println!("Saved {} chunks in {}ms", self.world.modified_chunks().len(),
+                stopwatch.elapsed().as_millis()),
+            Err(e) => println!("Failed to save world: {}", e)
+        }
+        self.last_save = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use net::client::{Client, ClientEvent};
+    use std::fs;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use world::block::block_type::keys;
+
+    const TIMEOUT: u64 = 20;
+
+    fn poll_until<F: FnMut(&mut Client, &mut World) -> bool>(client: &mut Client, world: &mut World, mut done: F) {
+        let start = Instant::now();
+        while !done(client, world) {
+            assert!(client.is_connected(), "client disconnected");
+            assert!(start.elapsed() < Duration::from_secs(TIMEOUT), "timed out waiting for server");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn plays_over_localhost() {
+        let directory = ::std::env::temp_dir().join(format!("minecraft-server-test-{}", ::std::process::id()));
+        let properties = ServerProperties {
+            world: directory.clone(),
+            view_distance: 2,
+            autosave_interval: 0,
+            port: 0,
+            ..ServerProperties::default()
+        };
+        let mut server = Server::start(properties).unwrap();
+        let address = format!("127.0.0.1:{}", server.port());
+        let stop = Arc::new(AtomicBool::new(false));
+        let running = stop.clone();
+        let handle = thread::spawn(move || {
+            while !running.load(Ordering::SeqCst) {
+                server.tick();
+                thread::sleep(Duration::from_millis(5));
+            }
+        });
+
+        let mut client = Client::connect(&address, "tester").unwrap();
+        let mut world = World::remote(2, 1);
+        let spawn = client.spawn();
+        let center = World::chunk_coordinates(&spawn);
+        poll_until(&mut client, &mut world, |c, w| {
+            c.poll(w);
+            w.chunk(&center).is_some()
+        });
+
+        let position = Vec3i::new(spawn.x.floor() as i32 + 1, spawn.y as i32 + 2, spawn.z.floor() as i32);
+        let dirt = BlockState::new(block_database::get().block_type(keys::DIRT).unwrap());
+        client.send_block_change(position, dirt).unwrap();
+        poll_until(&mut client, &mut world, |c, w| {
+            c.poll(w);
+            w.block_at(&position) == dirt
+        });
+
+        client.send_chat(&format!("  hello {}  ", "x".repeat(MAX_CHAT_LENGTH)));
+        let expected = format!("<tester> hello {}", "x".repeat(MAX_CHAT_LENGTH - 6));
+        poll_until(&mut client, &mut world, |c, w| {
+            c.poll(w).into_iter().any(|e| match e {
+                ClientEvent::Chat(message) => message == expected,
+                _ => false
+            })
+        });
+
+        stop.store(true, Ordering::SeqCst);
+        handle.join().unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
\ No newline at end of file
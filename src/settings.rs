@@ -0,0 +1,203 @@
+use serde_json;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use render::hdr_buffer::{DEFAULT_EXPOSURE, DEFAULT_BLOOM_STRENGTH};
+use render::ssao_buffer::SsaoQuality;
+use world::constants::{RENDER_DISTANCE, MIN_RENDER_DISTANCE, MAX_RENDER_DISTANCE};
+use input::controls::{Bindings, default_bindings};
+use input::gamepad::GamepadSettings;
+use util::noise::DEFAULT_SEED;
+
+pub const SETTINGS_FILE: &'static str = "./settings.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VideoSettings {
+    pub width: u32,
+    pub height: u32,
+    pub fov: f32,
+    pub ssao: bool,
+    pub ssao_quality: SsaoQuality,
+    pub exposure: f32,
+    pub bloom_strength: f32,
+    pub resource_packs: Vec<PathBuf>
+}
+
+impl Default for VideoSettings {
+    fn default() -> Self {
+        VideoSettings {
+            width: 1280,
+            height: 720,
+            fov: 80.0,
+            ssao: true,
+            ssao_quality: SsaoQuality::Medium,
+            exposure: DEFAULT_EXPOSURE,
+            bloom_strength: DEFAULT_BLOOM_STRENGTH,
+            resource_packs: Vec::new()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControlSettings {
+    pub mouse_speed: f32,
+    pub bindings: Bindings,
+    pub gamepad: GamepadSettings
+}
+
+impl Default for ControlSettings {
+    fn default() -> Self {
+        ControlSettings { mouse_speed: 0.05, bindings: default_bindings(), gamepad: GamepadSettings::default() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldSettings {
+    pub render_distance: i32,
+    pub threads: usize,
+    pub seed: u32
+}
+
+impl Default for WorldSettings {
+    fn default() -> Self {
+        WorldSettings { render_distance: RENDER_DISTANCE, threads: 2, seed: DEFAULT_SEED }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub video: VideoSettings,
+    pub controls: ControlSettings,
+    pub world: WorldSettings
+}
+
+impl Settings {
+    pub fn validate(&mut self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        {
+            let mut clamp = |name: &str, value: &mut f32, min: f32, max: f32| {
+                if !(*value >= min && *value <= max) {
+                    let clamped = if *value > max { max } else { min };
+                    warnings.push(format!("{} must be between {} and {}, using {}", name, min, max, clamped));
+                    *value = clamped;
+                }
+            };
+            clamp("video.fov", &mut self.video.fov, 30.0, 120.0);
+            clamp("video.exposure", &mut self.video.exposure, 0.05, 10.0);
+            clamp("video.bloom_strength", &mut self.video.bloom_strength, 0.0, 5.0);
+            clamp("controls.mouse_speed", &mut self.controls.mouse_speed, 0.001, 1.0);
+            clamp("controls.gamepad.deadzone", &mut self.controls.gamepad.deadzone, 0.0, 0.9);
+            clamp("controls.gamepad.curve", &mut self.controls.gamepad.curve, 0.2, 5.0);
+            clamp("controls.gamepad.look_speed", &mut self.controls.gamepad.look_speed, 1.0, 1000.0);
+            clamp("controls.gamepad.trigger_threshold", &mut self.controls.gamepad.trigger_threshold, -1.0, 1.0);
+        }
+        if self.video.width < 320 || self.video.height < 240 {
+            warnings.push(format!("video resolution {}x{} is too small, using 1280x720", self.video.width, self.video.height));
+            self.video.width = 1280;
+            self.video.height = 720;
+        }
+        if self.world.render_distance < MIN_RENDER_DISTANCE || self.world.render_distance > MAX_RENDER_DISTANCE {
+            let clamped = self.world.render_distance.max(MIN_RENDER_DISTANCE).min(MAX_RENDER_DISTANCE);
+            warnings.push(format!("world.render_distance must be between {} and {}, using {}",
+                                  MIN_RENDER_DISTANCE, MAX_RENDER_DISTANCE, clamped));
+            self.world.render_distance = clamped;
+        }
+        if self.world.threads < 1 || self.world.threads > 32 {
+            let clamped = self.world.threads.max(1).min(32);
+            warnings.push(format!("world.threads must be between 1 and 32, using {}", clamped));
+            self.world.threads = clamped;
+        }
+        warnings
+    }
+
+    pub fn apply_override(&mut self, key: &str, value: &str) -> Result<bool, String> {
+        fn parse<T: ::std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+            value.parse::<T>().map_err(|_| format!("Invalid value for {}: {}", key, value))
+        }
+        match key.to_lowercase().as_str() {
+            "width" => self.video.width = parse(key, value)?,
+            "height" => self.video.height = parse(key, value)?,
+            "fov" => self.video.fov = parse(key, value)?,
+            "ssao" => self.video.ssao = parse(key, value)?,
+            "exposure" => self.video.exposure = parse(key, value)?,
+            "bloom" => self.video.bloom_strength = parse(key, value)?,
+            "packs" => self.video.resource_packs = value.split(",").map(|p| PathBuf::from(p.trim())).collect(),
+            "mouse_speed" => self.controls.mouse_speed = parse(key, value)?,
+            "gamepad" => self.controls.gamepad.enabled = parse(key, value)?,
+            "render_distance" => self.world.render_distance = parse(key, value)?,
+            "threads" => self.world.threads = parse(key, value)?,
+            "seed" => self.world.seed = parse(key, value)?,
+            _ => return Ok(false)
+        }
+        Ok(true)
+    }
+}
+
+pub struct SettingsStore {
+    path: PathBuf,
+    saved: Settings,
+    current: Settings
+}
+
+impl SettingsStore {
+    pub fn load(path: &Path) -> SettingsStore {
+        let mut saved = match File::open(path) {
+            Ok(file) => serde_json::from_reader(BufReader::new(file)).unwrap_or_else(|e| {
+                println!("Failed to parse {}: {}, using defaults", path.display(), e);
+                Settings::default()
+            }),
+            Err(_) => Settings::default()
+        };
+        for warning in saved.validate() {
+            println!("Settings: {}", warning);
+        }
+        let store = SettingsStore { path: path.to_path_buf(), current: saved.clone(), saved };
+        if !path.exists() {
+            store.save();
+        }
+        store
+    }
+
+    pub fn apply_overrides(&mut self, overrides: &[(String, String)]) -> Vec<String> {
+        let mut unused = Vec::new();
+        for &(ref key, ref value) in overrides {
+            match self.current.apply_override(key, value) {
+                Ok(true) => {},
+                Ok(false) => unused.push(key.clone()),
+                Err(e) => println!("{}", e)
+            }
+        }
+        for warning in self.current.validate() {
+            println!("Settings: {}", warning);
+        }
+        unused
+    }
+
+    pub fn get(&self) -> &Settings {
+        &self.current
+    }
+
+    pub fn update<F: Fn(&mut Settings)>(&mut self, change: F) {
+        change(&mut self.current);
+        change(&mut self.saved);
+        self.current.validate();
+        self.saved.validate();
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Some(parent) = self.path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let result = File::create(&self.path)
+            .map_err(|e| format!("{}", e))
+            .and_then(|file| serde_json::to_writer_pretty(BufWriter::new(file), &self.saved).map_err(|e| format!("{}", e)));
+        if let Err(e) = result {
+            println!("Failed to save settings to {}: {}", self.path.display(), e);
+        }
+    }
+}
\ No newline at end of file
@@ -3,74 +3,113 @@ use GL::Gl;
 use shader::Type;
 use shader::shader_error::ShaderError;
 use std::os::raw::c_char;
-use math::{Vec3, Mat4};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use math::{Vec2, Vec3, Mat4};
+
+static RELOAD_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    static ref RELOAD_ERRORS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+}
+
+pub fn request_reload() {
+    RELOAD_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+pub fn take_reload_errors() -> Vec<String> {
+    RELOAD_ERRORS.lock().unwrap().drain(..).collect()
+}
+
+#[derive(Clone, Copy)]
+enum Uniform {
+    Mat4(Mat4),
+    Vec2(Vec2),
+    Vec3(Vec3),
+    Int(i32),
+    Float(f32)
+}
 
 impl Shader {
     pub fn new(gl: &Gl, shader_type: Type, include_geometry: bool) -> Result<Shader, ShaderError> {
-        unsafe {
-            let program = gl.CreateProgram();
-            let shader_str = shader_type.file_bytes_as_cstr(include_geometry).unwrap();
-
-            let v = gl.CreateShader(GL::VERTEX_SHADER);
-            let f = gl.CreateShader(GL::FRAGMENT_SHADER);
-
-            gl.ShaderSource(v, 1, &shader_str.vertex.as_ptr(), ::std::ptr::null());
-            gl.ShaderSource(f, 1, &shader_str.fragment.as_ptr(), ::std::ptr::null());
-
-            gl.CompileShader(v);
-            get_shader_status(v, gl)?;
-            gl.CompileShader(f);
-            get_shader_status(f, gl)?;
-
-            gl.AttachShader(program, v);
-            gl.AttachShader(program, f);
-            let mut g = 0;
-            if include_geometry {
-                g = gl.CreateShader(GL::GEOMETRY_SHADER);
-                gl.ShaderSource(g, 1, &shader_str.geometry.unwrap().as_ptr(), ::std::ptr::null());
-                gl.CompileShader(g);
-                get_shader_status(g, gl)?;
-                gl.AttachShader(program, g);
-            }
-            gl.LinkProgram(program);
-            get_program_status(program, gl)?;
-            gl.DeleteShader(v);
-            gl.DeleteShader(f);
-            if include_geometry {
-                gl.DeleteShader(g);
-            }
+        let program = compile_program(gl, shader_type, include_geometry)?;
+        Ok(Shader {
+            program: Cell::new(program),
+            gl: gl.clone(),
+            shader_type,
+            include_geometry,
+            generation: Cell::new(RELOAD_GENERATION.load(Ordering::SeqCst)),
+            uniforms: RefCell::new(HashMap::new())
+        })
+    }
 
-            Ok(Shader { program, gl: gl.clone() })
+    pub fn reload(&self) -> Result<(), ShaderError> {
+        self.generation.set(RELOAD_GENERATION.load(Ordering::SeqCst));
+        let program = compile_program(&self.gl, self.shader_type, self.include_geometry)?;
+        unsafe {
+            self.gl.DeleteProgram(self.program.get());
+            self.gl.UseProgram(program);
         }
+        self.program.set(program);
+        for (name, uniform) in self.uniforms.borrow().iter() {
+            self.apply(name, uniform);
+        }
+        Ok(())
     }
 
     pub fn bind(&self) {
+        if self.generation.get() != RELOAD_GENERATION.load(Ordering::SeqCst) {
+            if let Err(e) = self.reload() {
+                RELOAD_ERRORS.lock().unwrap().push(format!("Failed to reload {} shader: {}", self.shader_type.as_str(), e));
+            }
+        }
         unsafe {
-            self.gl.UseProgram(self.program);
+            self.gl.UseProgram(self.program.get());
         }
     }
 
     pub fn mat_4(&self, name: &str, value: &Mat4) {
-        unsafe {
-            self.gl.UniformMatrix4fv(self.get_location(name), 1, GL::FALSE, &value[0][0] as *const f32);
-        }
+        self.set(name, Uniform::Mat4(*value));
+    }
+
+    pub fn vec2(&self, name: &str, value: &Vec2) {
+        self.set(name, Uniform::Vec2(*value));
     }
 
     pub fn vec3(&self, name: &str, value: &Vec3) {
-        unsafe {
-            self.gl.Uniform3fv(self.get_location(name), 1, &value[0]);
-        }
+        self.set(name, Uniform::Vec3(*value));
     }
 
     pub fn int(&self, name: &str, value: i32) {
-        unsafe {
-            self.gl.Uniform1i(self.get_location(name), value);
-        }
+        self.set(name, Uniform::Int(value));
     }
 
     pub fn float(&self, name: &str, value: f32) {
+        self.set(name, Uniform::Float(value));
+    }
+
+    fn set(&self, name: &str, uniform: Uniform) {
+        self.apply(name, &uniform);
+        let mut uniforms = self.uniforms.borrow_mut();
+        if let Some(value) = uniforms.get_mut(name) {
+            *value = uniform;
+            return;
+        }
+        uniforms.insert(name.to_string(), uniform);
+    }
+
+    fn apply(&self, name: &str, uniform: &Uniform) {
+        let location = self.get_location(name);
         unsafe {
-            self.gl.Uniform1f(self.get_location(name), value);
+            match *uniform {
+                Uniform::Mat4(ref value) => self.gl.UniformMatrix4fv(location, 1, GL::FALSE, &value[0][0] as *const f32),
+                Uniform::Vec2(ref value) => self.gl.Uniform2fv(location, 1, &value[0]),
+                Uniform::Vec3(ref value) => self.gl.Uniform3fv(location, 1, &value[0]),
+                Uniform::Int(value) => self.gl.Uniform1i(location, value),
+                Uniform::Float(value) => self.gl.Uniform1f(location, value)
+            }
         }
     }
 
@@ -79,7 +118,45 @@ impl Shader {
             let cname = ::std::ffi::CString::new(name)
                 .expect(format!("Expected uniform name, got: {}", name).as_str());
 
-            self.gl.GetUniformLocation(self.program, cname.as_bytes_with_nul().as_ptr() as *const i8)
+            self.gl.GetUniformLocation(self.program.get(), cname.as_bytes_with_nul().as_ptr() as *const i8)
+        }
+    }
+}
+
+fn compile_program(gl: &Gl, shader_type: Type, include_geometry: bool) -> Result<u32, ShaderError> {
+    let shader_str = shader_type.file_bytes_as_cstr(include_geometry)
+        .map_err(|e| ShaderError::LoadError { message: format!("{}", e) })?;
+    unsafe {
+        let program = gl.CreateProgram();
+        let mut shaders = vec![(GL::VERTEX_SHADER, shader_str.vertex), (GL::FRAGMENT_SHADER, shader_str.fragment)];
+        if let Some(geometry) = shader_str.geometry {
+            shaders.push((GL::GEOMETRY_SHADER, geometry));
+        }
+
+        let mut compiled = Vec::new();
+        let mut result = Ok(());
+        for (kind, source) in shaders {
+            let shader = gl.CreateShader(kind);
+            gl.ShaderSource(shader, 1, &source.as_ptr(), ::std::ptr::null());
+            gl.CompileShader(shader);
+            compiled.push(shader);
+            result = get_shader_status(shader, gl);
+            if result.is_err() { break; }
+            gl.AttachShader(program, shader);
+        }
+        if result.is_ok() {
+            gl.LinkProgram(program);
+            result = get_program_status(program, gl);
+        }
+        for shader in compiled {
+            gl.DeleteShader(shader);
+        }
+        match result {
+            Ok(_) => Ok(program),
+            Err(e) => {
+                gl.DeleteProgram(program);
+                Err(e)
+            }
         }
     }
 }
@@ -117,14 +194,18 @@ fn get_shader_status(shader: u32, gl: &Gl) -> Result<(), ShaderError> {
 }
 
 pub struct Shader {
-    program: u32,
-    gl: Gl
+    program: Cell<u32>,
+    gl: Gl,
+    shader_type: Type,
+    include_geometry: bool,
+    generation: Cell<usize>,
+    uniforms: RefCell<HashMap<String, Uniform>>
 }
 
 impl Drop for Shader {
     fn drop(&mut self) {
         unsafe {
-            self.gl.DeleteProgram(self.program);
+            self.gl.DeleteProgram(self.program.get());
         }
     }
 }
\ No newline at end of file
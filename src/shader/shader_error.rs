@@ -1,6 +1,18 @@
+use std::fmt;
 
 #[derive(Debug)]
 pub enum ShaderError {
     ShaderCompileError { message: String },
-    ProgramCompileError { message: String }
+    ProgramCompileError { message: String },
+    LoadError { message: String }
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ShaderError::ShaderCompileError { ref message } |
+            ShaderError::ProgramCompileError { ref message } |
+            ShaderError::LoadError { ref message } => write!(f, "{}", message.split('\0').next().unwrap_or("").trim())
+        }
+    }
 }
\ No newline at end of file
@@ -1,14 +1,20 @@
 use std::ffi::CString;
-use std::fs::File;
-use std::io::Read;
+use std::io::{Error, ErrorKind};
+use util::resources;
 
+#[derive(Debug, Copy, Clone)]
 pub enum Type {
     Block,
     Text,
     CubeMap,
     Torch,
     Deferred,
-    ShadowMap
+    ShadowMap,
+    BrightPass,
+    Blur,
+    Tonemap,
+    Ssao,
+    SsaoBlur
 }
 
 pub struct ShaderString {
@@ -26,13 +32,18 @@ impl Type {
             CubeMap => "cube_map",
             Torch => "torch",
             Deferred => "deferred",
-            ShadowMap => "shadow_map"
+            ShadowMap => "shadow_map",
+            BrightPass => "bright_pass",
+            Blur => "blur",
+            Tonemap => "tonemap",
+            Ssao => "ssao",
+            SsaoBlur => "ssao_blur"
         }
     }
 
     pub fn file_bytes_as_cstr(&self, include_geometry: bool) -> Result<ShaderString, ::std::io::Error> {
         use self::Type::*;
-        let p: &str = &("./assets/shaders/".to_string() + self.as_str());
+        let p: &str = &("assets/shaders/".to_string() + self.as_str());
         let vertex = load_specific_shader(p, ".vert")?;
         let fragment = load_specific_shader(p, ".frag")?;
 
@@ -47,11 +58,10 @@ impl Type {
     }
 }
 
-fn load_specific_shader(filename: &str, extension: &str) -> Result<CString, ::std::io::Error> {
-    let mut f = File::open(format!("{}{}", filename, extension))
-        .expect(format!("Failed to open shader file: {}{}", filename, extension).as_str());
-    let mut file_bytes: Vec<u8> = Vec::new();
-    f.read_to_end(&mut file_bytes)?;
-    Ok(CString::new(file_bytes).expect(
-        format!("Failed to read shader to CString: {}{}", filename, extension).as_str()))
+fn load_specific_shader(filename: &str, extension: &str) -> Result<CString, Error> {
+    let path = format!("{}{}", filename, extension);
+    let file_bytes = resources::get().read(&path)
+        .map_err(|e| Error::new(ErrorKind::NotFound, e))?;
+    CString::new(file_bytes)
+        .map_err(|_| Error::new(ErrorKind::InvalidData, format!("Shader file contains a nul byte: {}", path)))
 }
\ No newline at end of file
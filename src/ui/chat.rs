@@ -0,0 +1,54 @@
+use glfw::Key;
+use input::events::{InputAction, InputEvent, InputQueue};
+use util::math::vec3;
+use ui::text::Text;
+use minecraft::net::protocol::MAX_CHAT_LENGTH;
+
+pub struct Chat {
+    input: Option<String>
+}
+
+impl Chat {
+    pub fn new() -> Chat {
+        Chat { input: None }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.input.is_some()
+    }
+
+    pub fn open(&mut self) {
+        self.input = Some(String::new());
+    }
+
+    pub fn update(&mut self, queue: &InputQueue) -> Option<String> {
+        for event in queue.events() {
+            let line = match self.input {
+                Some(ref mut line) => line,
+                None => return None
+            };
+            match *event {
+                InputEvent::Char(c) if line.chars().count() < MAX_CHAT_LENGTH => line.push(c),
+                ref e if e.is_key(Key::Backspace, InputAction::Press) || e.is_key(Key::Backspace, InputAction::Repeat) => {
+                    line.pop();
+                },
+                ref e if e.is_key(Key::Escape, InputAction::Press) => { self.input = None; },
+                ref e if e.is_key(Key::Enter, InputAction::Press) || e.is_key(Key::KpEnter, InputAction::Press) => {
+                    let message = line.trim().to_string();
+                    self.input = None;
+                    if !message.is_empty() {
+                        return Some(message);
+                    }
+                },
+                _ => {}
+            }
+        }
+        None
+    }
+
+    pub fn draw(&self, text: &mut Text) {
+        if let Some(ref line) = self.input {
+            text.render(&format!("> {}_", line), 10.0, 40.0, 0.4, &vec3(1.0, 1.0, 1.0));
+        }
+    }
+}
\ No newline at end of file
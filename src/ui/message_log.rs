@@ -0,0 +1,34 @@
+use util::math::Vec3;
+use ui::text::Text;
+use window::Window;
+
+const MESSAGE_DURATION: u64 = 6000;
+const MAX_MESSAGES: usize = 12;
+const LINE_HEIGHT: f32 = 18.0;
+
+pub struct MessageLog {
+    messages: Vec<(String, Vec3, u64)>
+}
+
+impl MessageLog {
+    pub fn new() -> MessageLog {
+        MessageLog { messages: Vec::new() }
+    }
+
+    pub fn push(&mut self, message: &str, color: Vec3, time: u64) {
+        println!("{}", message);
+        for line in message.lines().filter(|l| !l.trim().is_empty()) {
+            self.messages.push((line.to_string(), color, time));
+        }
+        let overflow = self.messages.len().saturating_sub(MAX_MESSAGES);
+        self.messages.drain(..overflow);
+    }
+
+    pub fn draw(&mut self, text: &mut Text, window: &Window, time: u64) {
+        self.messages.retain(|&(_, _, t)| time.saturating_sub(t) < MESSAGE_DURATION);
+        let top = window.size().1 as f32 - 60.0;
+        for (i, &(ref message, ref color, _)) in self.messages.iter().enumerate() {
+            text.render(message, 10.0, top - i as f32 * LINE_HEIGHT, 0.35, color);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,3 @@
+pub mod text;
+pub mod message_log;
+pub mod chat;
\ No newline at end of file
@@ -1,8 +1,9 @@
 use GL;
 use GL::Gl;
-use freetype;
+use freetype::{self, Library, Face};
 use math::{vec2, Vec2i, Vec3, ortho};
 use std::collections::HashMap;
+use std::rc::Rc;
 use shader::{Shader, Type as ShaderType};
 use util::resources;
 
@@ -40,7 +41,7 @@ impl Text {
             shader.bind();
             shader.mat_4("projection", &ortho(0.0, screen_size.0 as f32, 0.0, screen_size.1 as f32, -1.0, 100.0));
 
-            let face = resources::load_font(file).unwrap();
+            let face = load_font(file).unwrap();
             face.set_pixel_sizes(0, 48);
 
             gl.PixelStorei(GL::UNPACK_ALIGNMENT, 1);
@@ -95,7 +96,10 @@ impl Text {
 
             let mut xx = x;
             for c in text.chars() {
-                let ch = self.characters.get(&c).unwrap();
+                let ch = match self.characters.get(&c) {
+                    Some(ch) => ch,
+                    None => continue
+                };
 
                 let x_pos = xx + ch.bearing.x as f32 * s;
                 let y_pos = y - (ch.size.y - ch.bearing.y) as f32 * s;
@@ -131,4 +135,16 @@ impl Drop for Text {
             self.gl.DeleteVertexArrays(1, &mut self.vao);
         }
     }
+}
+
+fn load_font(filename: &str) -> Result<Face, String> {
+    let lib = match Library::init() {
+        Ok(l) => l,
+        Err(e) => return Err(format!("Failed to initialize freetype: {}", e))
+    };
+    let bytes = resources::get().read(&format!("assets/fonts/{}", filename))?;
+    match lib.new_memory_face(Rc::new(bytes), 0) {
+        Ok(f) => Ok(f),
+        Err(e) => Err(format!("Failed to load font: {}", e))
+    }
 }
\ No newline at end of file
@@ -1,8 +1,5 @@
 pub mod math;
 pub mod timer;
-pub mod controls;
 pub mod vertex;
-pub mod text;
 pub mod resources;
-pub mod noise;
-#[macro_use] pub mod macros;
\ No newline at end of file
+pub mod noise;
\ No newline at end of file
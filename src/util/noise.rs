@@ -1,23 +1,48 @@
-use noise::{NoiseFn, Fbm, MultiFractal};
+use noise::{NoiseFn, Fbm, MultiFractal, Seedable};
 use math::{Vec3i, Vec2i};
-use world::constants::CHUNK_HEIGHT;
+use std::sync::RwLock;
+use world::constants::{CHUNK_HEIGHT, SEA_LEVEL};
+
+pub const DEFAULT_SEED: u32 = 23759;
 
 lazy_static! {
-    static ref BLOCK_NOISE: Fbm = Fbm::new()
-        .set_octaves(2)
-        .set_frequency(0.07)
-        .set_persistence(0.4);
+    static ref NOISE: RwLock<WorldNoise> = RwLock::new(WorldNoise::new(DEFAULT_SEED));
+}
+
+struct WorldNoise {
+    seed: u32,
+    block: Fbm,
+    height: Fbm
+}
+
+impl WorldNoise {
+    fn new(seed: u32) -> WorldNoise {
+        WorldNoise {
+            seed,
+            block: Fbm::new()
+                .set_octaves(2)
+                .set_frequency(0.07)
+                .set_persistence(0.4)
+                .set_seed(seed),
+            height: Fbm::new()
+                .set_octaves(6)
+                .set_frequency(0.0008)
+                .set_persistence(0.35)
+                .set_seed(seed)
+                .set_lacunarity(3.0)
+        }
+    }
+}
 
-    static ref HEIGHT_NOISE: Fbm = Fbm::new()
-        .set_octaves(6)
-        .set_frequency(0.0008)
-        .set_persistence(0.35)
-        .set_seed(23759)
-        .set_lacunarity(3.0);
+pub fn set_seed(seed: u32) {
+    let mut noise = NOISE.write().unwrap();
+    if noise.seed != seed {
+        *noise = WorldNoise::new(seed);
+    }
 }
 
 pub fn noise_3i(p: Vec3i, threshold: usize) -> usize {
-    let value = BLOCK_NOISE.get([
+    let value = NOISE.read().unwrap().block.get([
         p.x as f64 + 0.33, p.y as f64 + 0.11, p.z as f64 + 0.33
     ]).abs() * 2.0;
     (value * threshold as f64) as usize
@@ -25,6 +50,6 @@ pub fn noise_3i(p: Vec3i, threshold: usize) -> usize {
 
 pub fn height(world_x: i32, world_z: i32) -> i32 {
     (
-        HEIGHT_NOISE.get([world_x as f64, world_z as f64]).abs() * CHUNK_HEIGHT as f64
-    ) as i32 + 100
+        NOISE.read().unwrap().height.get([world_x as f64, world_z as f64]).abs() * CHUNK_HEIGHT as f64
+    ) as i32 + SEA_LEVEL
 }
\ No newline at end of file
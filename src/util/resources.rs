@@ -1,15 +1,136 @@
-use std::ffi::CString;
-use std::fs::File;
+use std::collections::BTreeSet;
+use std::fs::{self, File};
 use std::io::Read;
-use freetype::{Library, Face};
-
-pub fn load_font(filename: &str) -> Result<Face, String> {
-    let lib = match Library::init() {
-        Ok(l) => l,
-        Err(e) => return Err(format!("Failed to initialize freetype: {}", e))
-    };
-    match lib.new_face(("./assets/fonts/".to_owned() + filename).as_str(), 0) {
-        Ok(f) => Ok(f),
-        Err(e) => Err(format!("Failed to load font: {}", e))
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use zip::ZipArchive;
+
+const DEFAULT_ROOT: &'static str = ".";
+
+lazy_static! {
+    static ref RESOURCE_MANAGER: RwLock<Arc<ResourceManager>> = RwLock::new(Arc::new(ResourceManager::new(Vec::new())));
+}
+
+pub fn get() -> Arc<ResourceManager> {
+    RESOURCE_MANAGER.read().unwrap().clone()
+}
+
+pub fn set_packs(packs: Vec<PathBuf>) {
+    let manager = ResourceManager::new(packs);
+    for pack in manager.packs.iter() {
+        println!("Using resource pack: {}", pack.path().display());
+    }
+    *RESOURCE_MANAGER.write().unwrap() = Arc::new(manager);
+}
+
+pub enum Pack {
+    Directory(PathBuf),
+    Zip(PathBuf)
+}
+
+impl Pack {
+    pub fn open(path: PathBuf) -> Result<Pack, String> {
+        if path.is_dir() {
+            Ok(Pack::Directory(path))
+        } else if path.is_file() {
+            ZipArchive::new(File::open(&path).map_err(|e| format!("Failed to open pack {}: {}", path.display(), e))?)
+                .map_err(|e| format!("Invalid zip pack {}: {}", path.display(), e))?;
+            Ok(Pack::Zip(path))
+        } else {
+            Err(format!("Resource pack not found: {}", path.display()))
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        match *self {
+            Pack::Directory(ref path) | Pack::Zip(ref path) => path
+        }
+    }
+
+    fn read(&self, name: &str) -> Option<Result<Vec<u8>, String>> {
+        let mut bytes = Vec::new();
+        match *self {
+            Pack::Directory(ref root) => {
+                let path = root.join(name);
+                if !path.is_file() { return None; }
+                let result = File::open(&path).and_then(|mut f| f.read_to_end(&mut bytes));
+                Some(result.map(|_| bytes).map_err(|e| format!("Failed to read {}: {}", path.display(), e)))
+            },
+            Pack::Zip(ref path) => {
+                let mut archive = match File::open(path).ok().and_then(|f| ZipArchive::new(f).ok()) {
+                    Some(archive) => archive,
+                    None => return Some(Err(format!("Failed to open pack {}", path.display())))
+                };
+                let mut file = match archive.by_name(name) {
+                    Ok(file) => file,
+                    Err(_) => return None
+                };
+                Some(file.read_to_end(&mut bytes).map(|_| bytes)
+                    .map_err(|e| format!("Failed to read {} from {}: {}", name, path.display(), e)))
+            }
+        }
+    }
+
+    fn list(&self, directory: &str) -> Vec<String> {
+        match *self {
+            Pack::Directory(ref root) => fs::read_dir(root.join(directory))
+                .map(|entries| entries.filter_map(|e| e.ok())
+                    .filter(|e| e.path().is_file())
+                    .filter_map(|e| e.file_name().to_str().map(|n| n.to_string()))
+                    .collect())
+                .unwrap_or(Vec::new()),
+            Pack::Zip(ref path) => {
+                let mut archive = match File::open(path).ok().and_then(|f| ZipArchive::new(f).ok()) {
+                    Some(archive) => archive,
+                    None => return Vec::new()
+                };
+                let prefix = format!("{}/", directory.trim_end_matches('/'));
+                (0..archive.len())
+                    .filter_map(|i| archive.by_index(i).ok().map(|f| f.name().to_string()))
+                    .filter(|name| name.starts_with(&prefix))
+                    .map(|name| name[prefix.len()..].to_string())
+                    .filter(|name| !name.is_empty() && !name.contains('/'))
+                    .collect()
+            }
+        }
+    }
+}
+
+pub struct ResourceManager {
+    packs: Vec<Pack>,
+    defaults: Pack
+}
+
+impl ResourceManager {
+    pub fn new(paths: Vec<PathBuf>) -> ResourceManager {
+        let packs = paths.into_iter().filter_map(|path| Pack::open(path)
+            .map_err(|e| println!("{}", e)).ok()).collect();
+        ResourceManager { packs, defaults: Pack::Directory(PathBuf::from(DEFAULT_ROOT)) }
+    }
+
+    fn stack<'a>(&'a self) -> impl Iterator<Item = &'a Pack> + 'a {
+        self.packs.iter().chain(::std::iter::once(&self.defaults))
+    }
+
+    pub fn read(&self, name: &str) -> Result<Vec<u8>, String> {
+        for pack in self.stack() {
+            if let Some(result) = pack.read(name) {
+                return result;
+            }
+        }
+        Err(format!("Resource not found: {}", name))
+    }
+
+    pub fn read_to_string(&self, name: &str) -> Result<String, String> {
+        String::from_utf8(self.read(name)?).map_err(|e| format!("Resource {} is not valid UTF-8: {}", name, e))
+    }
+
+    pub fn list(&self, directory: &str, extension: &str) -> Vec<String> {
+        let suffix = format!(".{}", extension);
+        let names: BTreeSet<String> = self.stack()
+            .flat_map(|pack| pack.list(directory))
+            .filter(|name| name.ends_with(&suffix))
+            .collect();
+        names.into_iter().map(|name| format!("{}/{}", directory, name)).collect()
     }
 }
\ No newline at end of file
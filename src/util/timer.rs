@@ -1,76 +1,86 @@
-use std::time::{SystemTime, UNIX_EPOCH};
-use util::text::Text;
-use window::Window;
+use std::time::{Duration, Instant};
+
+pub const TICK_RATE: u32 = 60;
+pub const TICK_MILLIS: f32 = 1000.0 / TICK_RATE as f32;
+const MAX_FRAME_TIME: u64 = 250;
 
 pub struct Timer {
-    last_time: u64,
-    frame_leap: u64,
-    delta: u64,
+    start: Instant,
+    last_frame: Instant,
+    last_fps_update: Instant,
+    accumulator: Duration,
     current_frames: u64,
     pub fps: u64
 }
 
 impl Timer {
     pub fn new() -> Timer {
+        let now = Instant::now();
         Timer {
-            last_time: get_elapsed_time(),
-            frame_leap: get_elapsed_time(),
-            delta: get_elapsed_time(),
+            start: now,
+            last_frame: now,
+            last_fps_update: now,
+            accumulator: Duration::from_millis(0),
             current_frames: 0,
             fps: 0,
         }
     }
 
-    pub fn frame_leap(&self) -> u32 {
-        self.frame_leap as u32
+    pub fn elapsed(&self) -> u64 {
+        millis(self.last_frame.duration_since(self.start))
     }
 
-    pub fn tick(&mut self) {
-        let last_frame = self.delta;
-        self.delta = get_elapsed_time();
-        self.frame_leap = self.delta - last_frame;
+    pub fn tick(&mut self) -> u32 {
+        let now = Instant::now();
+        let frame_time = now.duration_since(self.last_frame).min(Duration::from_millis(MAX_FRAME_TIME));
+        self.last_frame = now;
+        self.accumulator += frame_time;
 
-        if self.delta - self.last_time > 1000 {
+        if now.duration_since(self.last_fps_update) > Duration::from_secs(1) {
             self.fps = self.current_frames;
             self.current_frames = 0;
-            self.last_time = self.delta;
+            self.last_fps_update = now;
         }
-
         self.current_frames += 1;
+
+        let tick = tick_duration();
+        let mut ticks = 0;
+        while self.accumulator >= tick {
+            self.accumulator -= tick;
+            ticks += 1;
+        }
+        ticks
     }
 
-    pub fn draw_frames(&self, text: &mut Text, window: &Window) {
-        text.render(
-            &format!("{}", self.fps),
-            10.0, window.size().1 as f32 - 30.0, 0.5,
-            &::math::vec3(1.0, 1.0, 0.0)
-        );
+    pub fn interpolation(&self) -> f32 {
+        (self.accumulator.as_secs_f64() / tick_duration().as_secs_f64()) as f32
+    }
+
+    pub fn time_to_next_tick(&self) -> Duration {
+        tick_duration().checked_sub(self.accumulator).unwrap_or(Duration::from_millis(0))
     }
 }
 
 pub struct StopWatch {
-    start: u64
+    start: Instant
 }
 
 impl StopWatch {
     pub fn start() -> StopWatch {
         StopWatch {
-            start: get_elapsed_time()
+            start: Instant::now()
         }
     }
 
     pub fn since_start(&self) -> u64 {
-        get_elapsed_time() - self.start
+        millis(self.start.elapsed())
     }
 }
 
-fn get_elapsed_time() -> u64 {
-    let start = SystemTime::now();
-
-    let since_epoch = start.duration_since(UNIX_EPOCH)
-        .expect("Time went backwards");
+fn tick_duration() -> Duration {
+    Duration::from_nanos(1_000_000_000 / TICK_RATE as u64)
+}
 
-    since_epoch.as_secs() * 1000
-        + since_epoch.subsec_nanos() as u64 / 1_000_000
-        + since_epoch.subsec_nanos() as u64 / 100_000_000
+fn millis(duration: Duration) -> u64 {
+    duration.as_secs() * 1000 + duration.subsec_millis() as u64
 }
\ No newline at end of file
@@ -1,9 +1,11 @@
 use util::math::{Vec3, Vec2, Mat4};
 use world::block::block::Block;
 
+#[repr(C)]
 #[derive(Copy, Clone, Debug)]
 pub struct Vertex {
     pub position: Vec3,
     pub uv: Vec2,
-    pub normal: Vec3
+    pub normal: Vec3,
+    pub layer: f32
 }
\ No newline at end of file
@@ -1,18 +1,28 @@
 use GL;
 use GL::Gl;
 use glfw;
-use glfw::{Action, Context, Key};
+use glfw::Context;
 use std::sync::mpsc::Receiver;
+use input::events::{InputEvent, InputQueue};
 
 pub struct Window {
     glfw: glfw::Glfw,
     window: glfw::Window,
     events: Receiver<(f64, glfw::WindowEvent)>,
+    input: InputQueue,
     is_open: bool
 }
 
 impl Window {
     pub fn new(width: u32, height: u32) -> Window {
+        Window::create(width, height, true)
+    }
+
+    pub fn headless(width: u32, height: u32) -> Window {
+        Window::create(width, height, false)
+    }
+
+    fn create(width: u32, height: u32, visible: bool) -> Window {
         let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
 
         glfw.window_hint(glfw::WindowHint::ContextVersion(4, 1));
@@ -21,25 +31,32 @@ impl Window {
         ));
         glfw.window_hint(glfw::WindowHint::DoubleBuffer(true));
         glfw.window_hint(glfw::WindowHint::Resizable(false));
+        glfw.window_hint(glfw::WindowHint::Visible(visible));
         #[cfg(target_os = "macos")]
             glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
 
         let (mut window, events) = glfw.create_window(width, height, "Game", glfw::WindowMode::Windowed)
             .expect("Failed to create window");
 
-        glfw.with_primary_monitor_mut(|_: &mut _, m: Option<&glfw::Monitor>| {
-            let monitor = m.unwrap();
-            let (x, y) = monitor.get_physical_size();
-            let mode: glfw::VidMode = monitor.get_video_mode().unwrap();
-            window.set_monitor(glfw::WindowMode::Windowed, x, y, width,
-                               height, Some(mode.refresh_rate));
-        });
+        if visible {
+            glfw.with_primary_monitor_mut(|_: &mut _, m: Option<&glfw::Monitor>| {
+                let monitor = m.unwrap();
+                let (x, y) = monitor.get_physical_size();
+                let mode: glfw::VidMode = monitor.get_video_mode().unwrap();
+                window.set_monitor(glfw::WindowMode::Windowed, x, y, width,
+                                   height, Some(mode.refresh_rate));
+            });
+        }
 
         window.make_current();
         window.set_key_polling(true);
+        window.set_char_polling(true);
+        window.set_mouse_button_polling(true);
         window.set_framebuffer_size_polling(true);
         window.set_cursor_enter_polling(true);
-        window.set_cursor_mode(glfw::CursorMode::Disabled);
+        if visible {
+            window.set_cursor_mode(glfw::CursorMode::Disabled);
+        }
         window.set_cursor_pos_polling(true);
         window.set_scroll_polling(true);
         window.set_sticky_keys(true);
@@ -48,6 +65,7 @@ impl Window {
             glfw,
             window,
             events,
+            input: InputQueue::new(),
             is_open: true,
         }
     }
@@ -75,6 +93,7 @@ impl Window {
 
     pub fn process_events(&mut self, gl: &Gl) {
         self.glfw.poll_events();
+        self.input.clear();
 
         unsafe {
             gl.ClearColor(0.0, 0.0, 0.0, 1.0);
@@ -82,12 +101,11 @@ impl Window {
         }
 
         for (_, event) in glfw::flush_messages(&self.events) {
-            match event {
-                glfw::WindowEvent::FramebufferSize(width, height) => unsafe {
-                    gl.Viewport(0, 0, width, height);
-                },
-                glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => self.is_open = false,
-                _ => {}
+            if let glfw::WindowEvent::FramebufferSize(width, height) = event {
+                unsafe { gl.Viewport(0, 0, width, height); }
+            }
+            if let Some(input) = InputEvent::from_window_event(&event) {
+                self.input.push(input);
             }
         }
         if self.window.should_close() {
@@ -95,6 +113,14 @@ impl Window {
         }
     }
 
+    pub fn input(&self) -> &InputQueue {
+        &self.input
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
     pub fn get_window(&self) -> &glfw::Window {
         &self.window
     }
@@ -6,37 +6,75 @@ use std::fs::File;
 use std::io::BufReader;
 use std::io::prelude::*;
 
-use math::{Vec2, vec2, Vec3, vec3, Vec3i, Mat4, One, Ext::{scale, rotate, translate}};
+use math::{Vec2, vec2, Vec3, vec3, Vec3i, Mat4, One, Ext::{rotate, translate}};
 use world::block::block_type::{BlockType, UvCoords};
 use util::vertex::Vertex;
 use world::block::block_light::BlockLight;
+use world::block::block_state::{StateProperty, Placement};
+use world::block::block_model::BlockModel;
+use world::block::texture_array::{self, TextureAnimation};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BlockList {
-    pub blocks: Vec<Block>
+    pub blocks: Vec<Block>,
+    #[serde(default)]
+    pub animations: Vec<TextureAnimation>
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Block {
+    pub key: String,
+    #[serde(skip)]
     pub m_type: BlockType,
-    pub id: u8,
     pub opaque: bool,
     pub has_texture: bool,
-    pub texture_top: Option<Vec<f32>>,
-    pub texture_bottom: Option<Vec<f32>>,
-    pub texture_front: Option<Vec<f32>>,
-    pub texture_back: Option<Vec<f32>>,
-    pub texture_left: Option<Vec<f32>>,
-    pub texture_right: Option<Vec<f32>>,
-    pub scale_x: Option<f32>,
-    pub scale_y: Option<f32>,
+    pub texture_top: Option<String>,
+    pub texture_bottom: Option<String>,
+    pub texture_front: Option<String>,
+    pub texture_back: Option<String>,
+    pub texture_left: Option<String>,
+    pub texture_right: Option<String>,
     pub light_emission: f32,
     pub light_color: Option<Vec<f32>>,
     pub natural: bool,
     pub max_height: i32,
     pub min_height: i32,
     pub rarity: i32,
-    pub model_scale: Option<f32>
+    pub model_scale: Option<f32>,
+    #[serde(default)]
+    pub properties: Vec<StateProperty>,
+    #[serde(default)]
+    pub placement: Placement,
+    pub model: Option<String>,
+    #[serde(skip)]
+    pub block_model: Option<BlockModel>
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Face {
+    Front,
+    Back,
+    Left,
+    Right,
+    Top,
+    Bottom
+}
+
+impl Face {
+    pub fn all() -> [Face; 6] {
+        [Face::Right, Face::Left, Face::Top, Face::Bottom, Face::Front, Face::Back]
+    }
+
+    pub fn normal(&self) -> Vec3 {
+        match *self {
+            Face::Front => vec3(0.0, 0.0, -1.0),
+            Face::Back => vec3(0.0, 0.0, 1.0),
+            Face::Left => vec3(-1.0, 0.0, 0.0),
+            Face::Right => vec3(1.0, 0.0, 0.0),
+            Face::Top => vec3(0.0, 1.0, 0.0),
+            Face::Bottom => vec3(0.0, -1.0, 0.0)
+        }
+    }
 }
 
 impl Block {
@@ -53,30 +91,31 @@ impl Block {
         }
     }
 
-    pub fn has_sub1_scale(&self) -> bool {
-        self.scale_x.unwrap_or(1.0) < 1.0
-        || self.scale_y.unwrap_or(1.0) < 1.0
-    }
-
-    pub fn scale(&self, model: &Mat4) -> Mat4 {
-        scale(&model, vec3(
-            self.scale_x.unwrap_or(1.0),
-            self.scale_y.unwrap_or(1.0),
-            self.scale_x.unwrap_or(1.0)
-        ))
+    pub fn has_property(&self, property: StateProperty) -> bool {
+        self.properties.contains(&property)
     }
 
     pub fn get_model(&self, translation: &Vec3, rot_x: f32, rot_y: f32) -> Mat4 {
         let mut m = translate(&Mat4::one(), translation.clone());
-        m = self.scale(&m);
         m = rotate(&m, rot_x, vec3(1.0, 0.0, 0.0));
         m = rotate(&m, rot_y, vec3(0.0, 1.0, 0.0));
         m
     }
 
-    fn get_uv(tex_coords: &Option<Vec<f32>>, scale_x: f32, scale_y: f32) -> UvCoords {
-        match tex_coords {
-            Some(coords) => BlockType::tex_coords(vec2(coords[0], coords[1]), scale_x, scale_y),
+    pub fn texture(&self, face: Face) -> &Option<String> {
+        match face {
+            Face::Front => &self.texture_front,
+            Face::Back => &self.texture_back,
+            Face::Left => &self.texture_left,
+            Face::Right => &self.texture_right,
+            Face::Top => &self.texture_top,
+            Face::Bottom => &self.texture_bottom
+        }
+    }
+
+    fn get_uv(texture: &Option<String>) -> UvCoords {
+        match texture {
+            Some(name) => texture_array::get().tex_coords(name),
             None => Default::default()
         }
     }
@@ -89,82 +128,96 @@ impl Block {
         )
     }
 
-    fn get_scale(&self, translate_from_scale: bool) -> Vec3 {
-        match translate_from_scale {
-            true => vec3(self.scale_x.unwrap_or(1.0), self.scale_y.unwrap_or(1.0), self.scale_x.unwrap_or(1.0)),
-            false => vec3(1.0,1.0,1.0)
+    pub fn build(&self, translation: &Vec3i, off: u32, is_culled: &dyn Fn(Face) -> bool) -> (Vec<Vertex>, Vec<u32>) {
+        if let Some(ref model) = self.block_model {
+            return model.build(self, translation, off, is_culled);
+        }
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        for face in Face::all().iter() {
+            if is_culled(*face) { continue; }
+            let (mut verts, mut inds) = self.build_face(*face, translation, off + vertices.len() as u32);
+            vertices.append(&mut verts);
+            indices.append(&mut inds);
+        }
+        (vertices, indices)
+    }
+
+    pub fn build_face(&self, face: Face, translation: &Vec3i, off: u32) -> (Vec<Vertex>, Vec<u32>) {
+        match face {
+            Face::Front => self.build_front_face(translation, off),
+            Face::Back => self.build_back_face(translation, off),
+            Face::Left => self.build_left_face(translation, off),
+            Face::Right => self.build_right_face(translation, off),
+            Face::Top => self.build_top_face(translation, off),
+            Face::Bottom => self.build_bottom_face(translation, off)
         }
     }
 
-    pub fn build_back_face(&self, translation: &Vec3i, off: u32, translate_from_scale: bool) -> (Vec<Vertex>, Vec<u32>) {
-        let uv = Block::get_uv(&self.texture_back, self.scale_x.unwrap_or(1.0), self.scale_y.unwrap_or(1.0));
+    pub fn build_back_face(&self, translation: &Vec3i, off: u32) -> (Vec<Vertex>, Vec<u32>) {
+        let uv = Block::get_uv(&self.texture_back);
         let t = self.vi_v(translation);
-        let s = self.get_scale(translate_from_scale);
         (vec![
-            Vertex { position: vec3(-0.5, -0.5, 0.5) * s + t, uv: uv.a, normal: vec3(0.0, 0.0, 1.0) },
-            Vertex { position: vec3( 0.5, -0.5, 0.5) * s + t, uv: uv.b, normal: vec3(0.0, 0.0, 1.0) },
-            Vertex { position: vec3( 0.5,  0.5, 0.5) * s + t, uv: uv.c, normal: vec3(0.0, 0.0, 1.0) },
-            Vertex { position: vec3(-0.5,  0.5, 0.5) * s + t, uv: uv.d, normal: vec3(0.0, 0.0, 1.0) },
+            Vertex { position: vec3(-0.5, -0.5, 0.5) + t, uv: uv.a, normal: vec3(0.0, 0.0, 1.0), layer: uv.layer },
+            Vertex { position: vec3( 0.5, -0.5, 0.5) + t, uv: uv.b, normal: vec3(0.0, 0.0, 1.0), layer: uv.layer },
+            Vertex { position: vec3( 0.5,  0.5, 0.5) + t, uv: uv.c, normal: vec3(0.0, 0.0, 1.0), layer: uv.layer },
+            Vertex { position: vec3(-0.5,  0.5, 0.5) + t, uv: uv.d, normal: vec3(0.0, 0.0, 1.0), layer: uv.layer },
         ], vec![off, off + 1, off + 2, off + 2, off + 3, off])
     }
 
-    pub fn build_front_face(&self, translation: &Vec3i, off: u32, translate_from_scale: bool) -> (Vec<Vertex>, Vec<u32>) {
-        let uv = Block::get_uv(&self.texture_front, self.scale_x.unwrap_or(1.0), self.scale_y.unwrap_or(1.0));
+    pub fn build_front_face(&self, translation: &Vec3i, off: u32) -> (Vec<Vertex>, Vec<u32>) {
+        let uv = Block::get_uv(&self.texture_front);
         let t = self.vi_v(translation);
-        let s = self.get_scale(translate_from_scale);
         (vec![
-            Vertex { position: vec3(-0.5, -0.5, -0.5) * s + t, uv: uv.a, normal: vec3(0.0, 0.0, -1.0) },
-            Vertex { position: vec3( 0.5, -0.5, -0.5) * s + t, uv: uv.b, normal: vec3(0.0, 0.0, -1.0) },
-            Vertex { position: vec3( 0.5,  0.5, -0.5) * s + t, uv: uv.c, normal: vec3(0.0, 0.0, -1.0) },
-            Vertex { position: vec3(-0.5,  0.5, -0.5) * s + t, uv: uv.d, normal: vec3(0.0, 0.0, -1.0) },
+            Vertex { position: vec3(-0.5, -0.5, -0.5) + t, uv: uv.a, normal: vec3(0.0, 0.0, -1.0), layer: uv.layer },
+            Vertex { position: vec3( 0.5, -0.5, -0.5) + t, uv: uv.b, normal: vec3(0.0, 0.0, -1.0), layer: uv.layer },
+            Vertex { position: vec3( 0.5,  0.5, -0.5) + t, uv: uv.c, normal: vec3(0.0, 0.0, -1.0), layer: uv.layer },
+            Vertex { position: vec3(-0.5,  0.5, -0.5) + t, uv: uv.d, normal: vec3(0.0, 0.0, -1.0), layer: uv.layer },
         ], vec![off, off + 2, off + 1, off + 2, off, off + 3])
     }
 
-    pub fn build_left_face(&self, translation: &Vec3i, off: u32, translate_from_scale: bool) -> (Vec<Vertex>, Vec<u32>) {
-        let uv = Block::get_uv(&self.texture_left, self.scale_x.unwrap_or(1.0), self.scale_y.unwrap_or(1.0));
+    pub fn build_left_face(&self, translation: &Vec3i, off: u32) -> (Vec<Vertex>, Vec<u32>) {
+        let uv = Block::get_uv(&self.texture_left);
         let t = self.vi_v(translation);
-        let s = self.get_scale(translate_from_scale);
         (vec![
-            Vertex { position: vec3(-0.5, -0.5, 0.5) * s + t, uv: uv.a, normal: vec3(-1.0, 0.0, 0.0) },
-            Vertex { position: vec3( -0.5, -0.5, -0.5) * s + t, uv: uv.b, normal: vec3(-1.0, 0.0, 0.0) },
-            Vertex { position: vec3( -0.5,  0.5, -0.5) * s + t, uv: uv.c, normal: vec3(-1.0, 0.0, 0.0) },
-            Vertex { position: vec3(-0.5,  0.5, 0.5) * s + t, uv: uv.d, normal: vec3(-1.0, 0.0, 0.0) },
+            Vertex { position: vec3(-0.5, -0.5, 0.5) + t, uv: uv.a, normal: vec3(-1.0, 0.0, 0.0), layer: uv.layer },
+            Vertex { position: vec3( -0.5, -0.5, -0.5) + t, uv: uv.b, normal: vec3(-1.0, 0.0, 0.0), layer: uv.layer },
+            Vertex { position: vec3( -0.5,  0.5, -0.5) + t, uv: uv.c, normal: vec3(-1.0, 0.0, 0.0), layer: uv.layer },
+            Vertex { position: vec3(-0.5,  0.5, 0.5) + t, uv: uv.d, normal: vec3(-1.0, 0.0, 0.0), layer: uv.layer },
         ], vec![off + 2, off, off + 3, off, off + 2, off + 1])
     }
 
-    pub fn build_right_face(&self, translation: &Vec3i, off: u32, translate_from_scale: bool) -> (Vec<Vertex>, Vec<u32>) {
-        let uv = Block::get_uv(&self.texture_right, self.scale_x.unwrap_or(1.0), self.scale_y.unwrap_or(1.0));
+    pub fn build_right_face(&self, translation: &Vec3i, off: u32) -> (Vec<Vertex>, Vec<u32>) {
+        let uv = Block::get_uv(&self.texture_right);
         let t = self.vi_v(translation);
-        let s = self.get_scale(translate_from_scale);
         (vec![
-            Vertex { position: vec3(0.5, -0.5, -0.5) * s + t, uv: uv.a, normal: vec3(1.0, 0.0, 0.0) },
-            Vertex { position: vec3( 0.5, -0.5, 0.5) * s + t, uv: uv.b, normal: vec3(1.0, 0.0, 0.0) },
-            Vertex { position: vec3( 0.5,  0.5, 0.5) * s + t, uv: uv.c, normal: vec3(1.0, 0.0, 0.0) },
-            Vertex { position: vec3(0.5,  0.5, -0.5) * s + t, uv: uv.d, normal: vec3(1.0, 0.0, 0.0) },
+            Vertex { position: vec3(0.5, -0.5, -0.5) + t, uv: uv.a, normal: vec3(1.0, 0.0, 0.0), layer: uv.layer },
+            Vertex { position: vec3( 0.5, -0.5, 0.5) + t, uv: uv.b, normal: vec3(1.0, 0.0, 0.0), layer: uv.layer },
+            Vertex { position: vec3( 0.5,  0.5, 0.5) + t, uv: uv.c, normal: vec3(1.0, 0.0, 0.0), layer: uv.layer },
+            Vertex { position: vec3(0.5,  0.5, -0.5) + t, uv: uv.d, normal: vec3(1.0, 0.0, 0.0), layer: uv.layer },
         ], vec![off + 3, off + 2, off + 1, off + 1, off, off + 3])
     }
 
-    pub fn build_top_face(&self, translation: &Vec3i, off: u32, translate_from_scale: bool) -> (Vec<Vertex>, Vec<u32>) {
-        let uv = Block::get_uv(&self.texture_top, self.scale_x.unwrap_or(1.0), self.scale_y.unwrap_or(1.0));
+    pub fn build_top_face(&self, translation: &Vec3i, off: u32) -> (Vec<Vertex>, Vec<u32>) {
+        let uv = Block::get_uv(&self.texture_top);
         let t = self.vi_v(translation);
-        let s = self.get_scale(translate_from_scale);
         (vec![
-            Vertex { position: vec3(-0.5, 0.5, -0.5) * s + t, uv: uv.a, normal: vec3(0.0, 1.0, 0.0) },
-            Vertex { position: vec3( 0.5, 0.5, -0.5) * s + t, uv: uv.b, normal: vec3(0.0, 1.0, 0.0) },
-            Vertex { position: vec3( 0.5,  0.5, 0.5) * s + t, uv: uv.c, normal: vec3(0.0, 1.0, 0.0) },
-            Vertex { position: vec3(-0.5,  0.5, 0.5) * s + t, uv: uv.d, normal: vec3(0.0, 1.0, 0.0) },
+            Vertex { position: vec3(-0.5, 0.5, -0.5) + t, uv: uv.a, normal: vec3(0.0, 1.0, 0.0), layer: uv.layer },
+            Vertex { position: vec3( 0.5, 0.5, -0.5) + t, uv: uv.b, normal: vec3(0.0, 1.0, 0.0), layer: uv.layer },
+            Vertex { position: vec3( 0.5,  0.5, 0.5) + t, uv: uv.c, normal: vec3(0.0, 1.0, 0.0), layer: uv.layer },
+            Vertex { position: vec3(-0.5,  0.5, 0.5) + t, uv: uv.d, normal: vec3(0.0, 1.0, 0.0), layer: uv.layer },
         ], vec![off + 3, off + 2, off + 1, off + 1, off, off + 3])
     }
 
-    pub fn build_bottom_face(&self, translation: &Vec3i, off: u32, translate_from_scale: bool) -> (Vec<Vertex>, Vec<u32>) {
-        let uv = Block::get_uv(&self.texture_bottom, self.scale_x.unwrap_or(1.0), self.scale_y.unwrap_or(1.0));
+    pub fn build_bottom_face(&self, translation: &Vec3i, off: u32) -> (Vec<Vertex>, Vec<u32>) {
+        let uv = Block::get_uv(&self.texture_bottom);
         let t = self.vi_v(translation);
-        let s = self.get_scale(translate_from_scale);
         (vec![
-            Vertex { position: vec3(-0.5, -0.5, -0.5) * s + t, uv: uv.a, normal: vec3(0.0, -1.0, 0.0) },
-            Vertex { position: vec3( 0.5, -0.5, -0.5) * s + t, uv: uv.b, normal: vec3(0.0, -1.0, 0.0) },
-            Vertex { position: vec3( 0.5,  -0.5, 0.5) * s + t, uv: uv.c, normal: vec3(0.0, -1.0, 0.0) },
-            Vertex { position: vec3(-0.5,  -0.5, 0.5) * s + t, uv: uv.d, normal: vec3(0.0, -1.0, 0.0) },
+            Vertex { position: vec3(-0.5, -0.5, -0.5) + t, uv: uv.a, normal: vec3(0.0, -1.0, 0.0), layer: uv.layer },
+            Vertex { position: vec3( 0.5, -0.5, -0.5) + t, uv: uv.b, normal: vec3(0.0, -1.0, 0.0), layer: uv.layer },
+            Vertex { position: vec3( 0.5,  -0.5, 0.5) + t, uv: uv.c, normal: vec3(0.0, -1.0, 0.0), layer: uv.layer },
+            Vertex { position: vec3(-0.5,  -0.5, 0.5) + t, uv: uv.d, normal: vec3(0.0, -1.0, 0.0), layer: uv.layer },
         ], vec![off, off + 1, off + 2, off + 2, off + 3, off])
     }
 }
\ No newline at end of file
@@ -1,25 +1,33 @@
 use std::collections::HashMap;
-use world::block::block_type::BlockType;
+use world::block::block_type::{BlockType, keys};
 use world::block::block::{Block, BlockList};
+use world::block::texture_array::{self, TextureAnimation, TextureArray};
+use world::block::block_registry::{self, BlockRegistryError};
 
 use serde;
 use serde_json;
 use serde_json::Error;
 
-use std::fs::File;
-use std::io::BufReader;
-use std::io::prelude::*;
+use util::resources;
 use std::rc::Rc;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
-const BLOCK_DEFINITION_SOURCE: &'static str = "./metadata/blocks.json";
+const BLOCK_DEFINITION_SOURCE: &'static str = "metadata/blocks.json";
 
 lazy_static! {
-    static ref BLOCK_DATABASE: BlockDatabase = BlockDatabase::new();
+    static ref BLOCK_DATABASE: RwLock<Arc<BlockDatabase>> = RwLock::new(Arc::new(BlockDatabase::new()));
 }
 
-pub fn get() -> &'static BlockDatabase {
-    &*BLOCK_DATABASE
+pub fn get() -> Arc<BlockDatabase> {
+    BLOCK_DATABASE.read().unwrap().clone()
+}
+
+pub fn reload() -> Result<(), String> {
+    let database = BlockDatabase::load(get().ids.clone()).map_err(|e| format!("{}", e))?;
+    let textures = TextureArray::load_for(&database)?;
+    *BLOCK_DATABASE.write().unwrap() = Arc::new(database);
+    texture_array::replace(textures);
+    Ok(())
 }
 
 pub struct BlockDatabase {
@@ -27,15 +35,30 @@ pub struct BlockDatabase {
     natural_blocks: HashMap<BlockType, Arc<Block>>,
     unnatural_blocks: HashMap<BlockType, Arc<Block>>,
     block_height_map: HashMap<i32, Vec<BlockType>>,
+    animations: Vec<TextureAnimation>,
+    ids: HashMap<String, BlockType>,
+    air: Arc<Block>
 }
 
 impl BlockDatabase {
     pub fn new() -> BlockDatabase {
+        BlockDatabase::load(HashMap::new()).unwrap_or_else(|e| panic!("Failed to load block database: {}", e))
+    }
+
+    pub fn load(mut ids: HashMap<String, BlockType>) -> Result<BlockDatabase, BlockRegistryError> {
         let mut block_map: HashMap<BlockType, Arc<Block>> = HashMap::new();
         let mut natural_blocks: HashMap<BlockType, Arc<Block>> = HashMap::new();
         let mut unnatural_blocks: HashMap<BlockType, Arc<Block>> = HashMap::new();
         let mut block_height_map: HashMap<i32, Vec<BlockType>> = HashMap::new();
-        for block in BlockDatabase::load_blocks().blocks {
+        let block_list = BlockDatabase::load_blocks()?;
+
+        ids.insert(keys::AIR.to_string(), BlockType::AIR);
+        let mut next_id = ids.values().map(|t| t.id() + 1).max().unwrap_or(1);
+        for mut block in block_list.blocks {
+            block.m_type = *ids.entry(block.key.clone()).or_insert_with(|| {
+                next_id += 1;
+                BlockType(next_id - 1)
+            });
             let block_arc = Arc::new(block);
             block_map.insert(block_arc.m_type, block_arc.clone());
             match block_arc.natural {
@@ -50,20 +73,22 @@ impl BlockDatabase {
                 }
             }
         }
-        BlockDatabase { blocks: block_map, natural_blocks, unnatural_blocks, block_height_map }
+        let air = block_map[&BlockType::AIR].clone();
+        Ok(BlockDatabase { blocks: block_map, natural_blocks, unnatural_blocks, block_height_map, animations: block_list.animations, ids, air })
+    }
+
+    fn load_blocks() -> Result<BlockList, BlockRegistryError> {
+        let contents = resources::get().read_to_string(BLOCK_DEFINITION_SOURCE)
+            .map_err(|message| BlockRegistryError::Io { file: BLOCK_DEFINITION_SOURCE.to_string(), message })?;
+        block_registry::parse(BLOCK_DEFINITION_SOURCE, &contents, &texture_array::texture_names())
     }
 
-    fn load_blocks() -> BlockList {
-        let file = File::open(BLOCK_DEFINITION_SOURCE).expect(&format!("Failed to open file: {}", BLOCK_DEFINITION_SOURCE));
-        let mut buf_reader = BufReader::new(file);
-        let mut contents = String::new();
-        buf_reader.read_to_string(&mut contents).expect(&format!("Failed to read contents of file: {}", BLOCK_DEFINITION_SOURCE));
-        serde_json::from_str::<BlockList>(&contents)
-            .expect(&format!("Failed to parse contents to string for file: {}", BLOCK_DEFINITION_SOURCE))
+    pub fn blocks_at_height(&self, height: i32) -> &[BlockType] {
+        self.block_height_map.get(&height).map(|b| b.as_slice()).unwrap_or(&[])
     }
 
-    pub fn blocks_at_height(&self, height: i32) -> &Vec<BlockType> {
-        self.block_height_map.get(&height).unwrap()
+    pub fn animations(&self) -> &Vec<TextureAnimation> {
+        &self.animations
     }
 
     pub fn natural_blocks(&self) -> &HashMap<BlockType, Arc<Block>> {
@@ -74,15 +99,23 @@ impl BlockDatabase {
         &self.unnatural_blocks
     }
 
+    pub fn block_type(&self, key: &str) -> Option<BlockType> {
+        self.ids.get(key).cloned().filter(|t| self.blocks.contains_key(t))
+    }
+
+    pub fn key(&self, id: BlockType) -> &str {
+        &self.get_block_ref(id).key
+    }
+
     pub fn get_block(&self, id: BlockType) -> Arc<Block> {
-        unsafe {
-            Arc::clone(self.blocks.get(&id).unwrap())
-        }
+        Arc::clone(self.get_block_ref(id))
+    }
+
+    fn get_block_ref(&self, id: BlockType) -> &Arc<Block> {
+        self.blocks.get(&id).unwrap_or(&self.air)
     }
 
     pub fn unwrap_block(&self, id: Option<&BlockType>) -> Arc<Block> {
-        unsafe {
-            Arc::clone(self.blocks.get(id.unwrap_or(&BlockType::Air)).unwrap())
-        }
+        self.get_block(*id.unwrap_or(&BlockType::AIR))
     }
 }
\ No newline at end of file
@@ -0,0 +1,72 @@
+use serde_json;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use world::block::block_database::BlockDatabase;
+use world::block::block_state::BlockState;
+use world::block::block_type::{BlockType, keys};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockIdMap {
+    keys: Vec<String>,
+    #[serde(skip)]
+    lookup: HashMap<String, u16>
+}
+
+impl BlockIdMap {
+    pub fn new() -> BlockIdMap {
+        BlockIdMap::from_keys(vec![keys::AIR.to_string()])
+    }
+
+    pub fn from_keys(keys: Vec<String>) -> BlockIdMap {
+        let lookup = keys.iter().enumerate().map(|(i, k)| (k.clone(), i as u16)).collect();
+        BlockIdMap { keys, lookup }
+    }
+
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    pub fn load(path: &Path) -> Result<BlockIdMap, String> {
+        if !path.exists() {
+            return Ok(BlockIdMap::new());
+        }
+        let file = File::open(path).map_err(|e| format!("Failed to open file {}: {}", path.display(), e))?;
+        let map: BlockIdMap = serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| format!("Failed to parse block id map {}: {}", path.display(), e))?;
+        Ok(BlockIdMap::from_keys(map.keys))
+    }
+
+    pub fn saved_id(&mut self, block_type: BlockType, database: &BlockDatabase) -> u16 {
+        let key = database.key(block_type);
+        if let Some(&id) = self.lookup.get(key) {
+            return id;
+        }
+        let id = self.keys.len() as u16;
+        self.keys.push(key.to_string());
+        self.lookup.insert(key.to_string(), id);
+        id
+    }
+
+    pub fn id(&self, block_type: BlockType, database: &BlockDatabase) -> Option<u16> {
+        self.lookup.get(database.key(block_type)).cloned()
+    }
+
+    pub fn state(&self, saved_id: u16, properties: [u8; 3], database: &BlockDatabase) -> Result<BlockState, String> {
+        let key = self.keys.get(saved_id as usize).ok_or_else(|| format!("Unknown block id {}", saved_id))?;
+        let block_type = database.block_type(key).ok_or_else(|| format!("Unknown block {}", key))?;
+        BlockState::from_properties(block_type, properties)
+            .ok_or_else(|| format!("Invalid block properties {:?}", properties))
+    }
+
+    pub fn block_type(&self, saved_id: u16, database: &BlockDatabase) -> BlockType {
+        match self.keys.get(saved_id as usize) {
+            Some(key) => database.block_type(key).unwrap_or_else(|| {
+                println!("Unknown block {} in saved data, replacing with air", key);
+                BlockType::AIR
+            }),
+            None => BlockType::AIR
+        }
+    }
+}
\ No newline at end of file
@@ -14,7 +14,7 @@ impl Default for BlockLight {
             color: Zero::zero(),
             strength: 0.0,
             position: Zero::zero(),
-            block_type: BlockType::Air
+            block_type: BlockType::AIR
         }
     }
 }
\ No newline at end of file
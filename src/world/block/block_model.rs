@@ -0,0 +1,171 @@
+use serde_json;
+use math::{vec2, vec3, Vec3, Vec3i, Mat4, One, vec4, Ext::rotate};
+use util::vertex::Vertex;
+use world::block::block::{Block, Face};
+use world::block::block_state::Axis;
+use world::block::texture_array;
+use util::resources;
+
+const MODEL_DIRECTORY: &'static str = "metadata/models";
+const MODEL_UNITS: f32 = 16.0;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockModel {
+    pub elements: Vec<ModelElement>
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModelElement {
+    pub from: [f32; 3],
+    pub to: [f32; 3],
+    pub rotation: Option<ElementRotation>,
+    pub faces: ModelFaces
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ElementRotation {
+    pub origin: [f32; 3],
+    pub axis: Axis,
+    pub angle: f32
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModelFaces {
+    pub front: Option<ModelFace>,
+    pub back: Option<ModelFace>,
+    pub left: Option<ModelFace>,
+    pub right: Option<ModelFace>,
+    pub top: Option<ModelFace>,
+    pub bottom: Option<ModelFace>
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModelFace {
+    pub texture: Option<String>,
+    pub uv: Option<[f32; 4]>,
+    #[serde(default)]
+    pub cull: bool
+}
+
+impl ModelFaces {
+    pub fn get(&self, face: Face) -> &Option<ModelFace> {
+        match face {
+            Face::Front => &self.front,
+            Face::Back => &self.back,
+            Face::Left => &self.left,
+            Face::Right => &self.right,
+            Face::Top => &self.top,
+            Face::Bottom => &self.bottom
+        }
+    }
+}
+
+impl BlockModel {
+    pub fn load(name: &str) -> Result<BlockModel, String> {
+        let path = format!("{}/{}.json", MODEL_DIRECTORY, name);
+        let contents = resources::get().read_to_string(&path)?;
+        serde_json::from_str::<BlockModel>(&contents)
+            .map_err(|e| format!("Failed to parse model {}: {}", path, e))
+    }
+
+    pub fn build(&self, block: &Block, translation: &Vec3i, off: u32, is_culled: &dyn Fn(Face) -> bool) -> (Vec<Vertex>, Vec<u32>) {
+        let t = vec3(translation.x as f32, translation.y as f32, translation.z as f32);
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        for element in &self.elements {
+            let rotation = element.rotation_matrix();
+            let origin = element.rotation.as_ref()
+                .map(|r| to_block_space(&r.origin))
+                .unwrap_or(vec3(0.0, 0.0, 0.0));
+            let (min, max) = (to_block_space(&element.from), to_block_space(&element.to));
+
+            for face in Face::all().iter() {
+                let model_face = match *element.faces.get(*face) {
+                    Some(ref f) => f,
+                    None => continue
+                };
+                if model_face.cull && is_culled(*face) { continue; }
+
+                let texture = match model_face.texture {
+                    Some(ref name) => name,
+                    None => match *block.texture(*face) {
+                        Some(ref name) => name,
+                        None => continue
+                    }
+                };
+                let layer = texture_array::get().layer(texture);
+                let uv = model_face.uv.unwrap_or(element.default_uv(*face));
+                let uvs = [
+                    vec2(uv[0], uv[3]) / MODEL_UNITS,
+                    vec2(uv[2], uv[3]) / MODEL_UNITS,
+                    vec2(uv[2], uv[1]) / MODEL_UNITS,
+                    vec2(uv[0], uv[1]) / MODEL_UNITS
+                ];
+                let n = rotation * vec4(face.normal().x, face.normal().y, face.normal().z, 0.0);
+                let normal = vec3(n.x, n.y, n.z);
+
+                let base = off + vertices.len() as u32;
+                for (corner, uv) in quad_corners(*face, &min, &max).iter().zip(uvs.iter()) {
+                    let p = rotation * vec4(corner.x - origin.x, corner.y - origin.y, corner.z - origin.z, 1.0);
+                    vertices.push(Vertex {
+                        position: vec3(p.x, p.y, p.z) + origin + t,
+                        uv: *uv, normal, layer
+                    });
+                }
+                indices.extend(quad_indices(*face).iter().map(|i| base + i));
+            }
+        }
+        (vertices, indices)
+    }
+}
+
+impl ModelElement {
+    fn rotation_matrix(&self) -> Mat4 {
+        match self.rotation {
+            Some(ref r) => {
+                let axis = match r.axis {
+                    Axis::X => vec3(1.0, 0.0, 0.0),
+                    Axis::Y => vec3(0.0, 1.0, 0.0),
+                    Axis::Z => vec3(0.0, 0.0, 1.0)
+                };
+                rotate(&Mat4::one(), r.angle.to_radians(), axis)
+            },
+            None => Mat4::one()
+        }
+    }
+
+    fn default_uv(&self, face: Face) -> [f32; 4] {
+        let (from, to) = (&self.from, &self.to);
+        match face {
+            Face::Front | Face::Back => [from[0], MODEL_UNITS - to[1], to[0], MODEL_UNITS - from[1]],
+            Face::Left => [MODEL_UNITS - to[2], MODEL_UNITS - to[1], MODEL_UNITS - from[2], MODEL_UNITS - from[1]],
+            Face::Right => [from[2], MODEL_UNITS - to[1], to[2], MODEL_UNITS - from[1]],
+            Face::Top | Face::Bottom => [from[0], MODEL_UNITS - to[2], to[0], MODEL_UNITS - from[2]]
+        }
+    }
+}
+
+fn to_block_space(v: &[f32; 3]) -> Vec3 {
+    vec3(v[0], v[1], v[2]) / MODEL_UNITS - vec3(0.5, 0.5, 0.5)
+}
+
+fn quad_corners(face: Face, min: &Vec3, max: &Vec3) -> [Vec3; 4] {
+    match face {
+        Face::Back => [vec3(min.x, min.y, max.z), vec3(max.x, min.y, max.z), vec3(max.x, max.y, max.z), vec3(min.x, max.y, max.z)],
+        Face::Front => [vec3(min.x, min.y, min.z), vec3(max.x, min.y, min.z), vec3(max.x, max.y, min.z), vec3(min.x, max.y, min.z)],
+        Face::Left => [vec3(min.x, min.y, max.z), vec3(min.x, min.y, min.z), vec3(min.x, max.y, min.z), vec3(min.x, max.y, max.z)],
+        Face::Right => [vec3(max.x, min.y, min.z), vec3(max.x, min.y, max.z), vec3(max.x, max.y, max.z), vec3(max.x, max.y, min.z)],
+        Face::Top => [vec3(min.x, max.y, min.z), vec3(max.x, max.y, min.z), vec3(max.x, max.y, max.z), vec3(min.x, max.y, max.z)],
+        Face::Bottom => [vec3(min.x, min.y, min.z), vec3(max.x, min.y, min.z), vec3(max.x, min.y, max.z), vec3(min.x, min.y, max.z)]
+    }
+}
+
+fn quad_indices(face: Face) -> [u32; 6] {
+    match face {
+        Face::Back | Face::Bottom => [0, 1, 2, 2, 3, 0],
+        Face::Front => [0, 2, 1, 2, 0, 3],
+        Face::Left => [2, 0, 3, 0, 2, 1],
+        Face::Right | Face::Top => [3, 2, 1, 1, 0, 3]
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use serde_json;
+use world::block::block::{Block, BlockList};
+use world::block::block_model::BlockModel;
+use world::block::block_type::keys;
+
+const MAX_RARITY: i32 = 100;
+
+#[derive(Debug)]
+pub struct RegistryProblem {
+    pub path: String,
+    pub message: String
+}
+
+#[derive(Debug)]
+pub enum BlockRegistryError {
+    Io { file: String, message: String },
+    Parse { file: String, line: usize, column: usize, message: String },
+    Invalid { file: String, problems: Vec<RegistryProblem> }
+}
+
+impl BlockRegistryError {
+    pub fn parse(file: &str, error: &serde_json::Error) -> BlockRegistryError {
+        BlockRegistryError::Parse {
+            file: file.to_string(),
+            line: error.line(),
+            column: error.column(),
+            message: format!("{}", error)
+        }
+    }
+}
+
+impl fmt::Display for BlockRegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BlockRegistryError::Io { ref file, ref message } => write!(f, "{}: {}", file, message),
+            BlockRegistryError::Parse { ref file, line, column, ref message } =>
+                write!(f, "{}:{}:{}: {}", file, line, column, message),
+            BlockRegistryError::Invalid { ref file, ref problems } => {
+                write!(f, "{} has {} problem(s)", file, problems.len())?;
+                for problem in problems {
+                    write!(f, "\n  {}: {}", problem.path, problem.message)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+pub fn parse(file: &str, contents: &str, textures: &[String]) -> Result<BlockList, BlockRegistryError> {
+    let mut list: BlockList = serde_json::from_str(contents).map_err(|e| BlockRegistryError::parse(file, &e))?;
+    let mut problems = validate(&list, textures);
+    for (i, block) in list.blocks.iter_mut().enumerate() {
+        if let Some(ref name) = block.model {
+            match BlockModel::load(name) {
+                Ok(model) => block.block_model = Some(model),
+                Err(e) => problems.push(RegistryProblem { path: format!("blocks[{}].model", i), message: e })
+            }
+        }
+    }
+    if !problems.is_empty() {
+        return Err(BlockRegistryError::Invalid { file: file.to_string(), problems });
+    }
+    Ok(list)
+}
+
+pub fn validate(list: &BlockList, textures: &[String]) -> Vec<RegistryProblem> {
+    let mut problems = Vec::new();
+    let mut defined: HashMap<&str, usize> = HashMap::new();
+    let textures: HashSet<&str> = textures.iter().map(|t| t.as_str()).collect();
+    let animations: HashSet<&str> = list.animations.iter().map(|a| a.name.as_str()).collect();
+
+    for (i, block) in list.blocks.iter().enumerate() {
+        let mut problem = |field: &str, message: String| problems.push(RegistryProblem {
+            path: format!("blocks[{}].{}", i, field), message
+        });
+
+        if !is_valid_key(&block.key) {
+            problem("key", format!("{} is not of the form namespace:name", block.key));
+        }
+        if let Some(first) = defined.insert(&block.key, i) {
+            problem("key", format!("{} is already defined by blocks[{}]", block.key, first));
+        }
+        if block.natural && block.min_height >= block.max_height {
+            problem("min_height", format!("min_height {} must be below max_height {}", block.min_height, block.max_height));
+        }
+        if block.rarity < 0 || block.rarity > MAX_RARITY {
+            problem("rarity", format!("rarity {} must be between 0 and {}", block.rarity, MAX_RARITY));
+        }
+        if let Some(ref color) = block.light_color {
+            if color.len() != 3 {
+                problem("light_color", format!("expected 3 components, found {}", color.len()));
+            }
+        }
+        if let Some(scale) = block.model_scale {
+            if scale <= 0.0 {
+                problem("model_scale", format!("scale {} must be positive", scale));
+            }
+        }
+        if block.has_texture && !has_any_texture(block) {
+            problem("has_texture", "block has no texture_* entries".to_string());
+        }
+        for &(field, texture) in texture_fields(block).iter() {
+            if let Some(ref name) = *texture {
+                if !textures.contains(name.as_str()) && !animations.contains(name.as_str()) {
+                    problem(field, format!("unknown texture {}", name));
+                }
+            }
+        }
+    }
+
+    for key in keys::REQUIRED.iter() {
+        if !defined.contains_key(key) {
+            problems.push(RegistryProblem {
+                path: "blocks".to_string(),
+                message: format!("missing definition for {}", key)
+            });
+        }
+    }
+
+    for (i, animation) in list.animations.iter().enumerate() {
+        if animation.frames.is_empty() {
+            problems.push(RegistryProblem {
+                path: format!("animations[{}].frames", i),
+                message: format!("animation {} has no frames", animation.name)
+            });
+        }
+        for (j, frame) in animation.frames.iter().enumerate() {
+            if !textures.contains(frame.texture.as_str()) {
+                problems.push(RegistryProblem {
+                    path: format!("animations[{}].frames[{}].texture", i, j),
+                    message: format!("unknown texture {}", frame.texture)
+                });
+            }
+            if frame.duration == 0 {
+                problems.push(RegistryProblem {
+                    path: format!("animations[{}].frames[{}].duration", i, j),
+                    message: "duration must be positive".to_string()
+                });
+            }
+        }
+    }
+    problems
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let valid = |part: &str| !part.is_empty() &&
+        part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '/');
+    match key.find(':') {
+        Some(i) => valid(&key[..i]) && valid(&key[i + 1..]),
+        None => false
+    }
+}
+
+fn texture_fields(block: &Block) -> [(&'static str, &Option<String>); 6] {
+    [
+        ("texture_top", &block.texture_top),
+        ("texture_bottom", &block.texture_bottom),
+        ("texture_front", &block.texture_front),
+        ("texture_back", &block.texture_back),
+        ("texture_left", &block.texture_left),
+        ("texture_right", &block.texture_right)
+    ]
+}
+
+fn has_any_texture(block: &Block) -> bool {
+    block.texture_top.is_some() || block.texture_bottom.is_some() || block.texture_front.is_some() ||
+        block.texture_back.is_some() || block.texture_left.is_some() || block.texture_right.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUIRED_BLOCKS: &'static str = r#"
+        {"key": "minecraft:air", "opaque": false, "has_texture": false, "light_emission": 0, "natural": false, "max_height": 0, "min_height": 0, "rarity": 0},
+        {"key": "minecraft:bedrock", "opaque": true, "has_texture": true, "texture_top": "bedrock", "light_emission": 0, "natural": false, "max_height": 0, "min_height": 0, "rarity": 0},
+        {"key": "minecraft:dirt", "opaque": true, "has_texture": true, "texture_top": "dirt", "light_emission": 0, "natural": true, "max_height": 256, "min_height": 5, "rarity": 16},
+        {"key": "minecraft:grass", "opaque": true, "has_texture": true, "texture_top": "grass", "light_emission": 0, "natural": false, "max_height": 0, "min_height": 0, "rarity": 0},
+        {"key": "minecraft:sun", "opaque": false, "has_texture": false, "light_emission": 1, "light_color": [1, 1, 1], "natural": false, "max_height": 0, "min_height": 0, "rarity": 0}"#;
+
+    fn fixture(blocks: &str, animations: &str) -> String {
+        let blocks = if blocks.is_empty() { REQUIRED_BLOCKS.to_string() } else { format!("{}, {}", REQUIRED_BLOCKS, blocks) };
+        format!(r#"{{"blocks": [{}], "animations": [{}]}}"#, blocks, animations)
+    }
+
+    fn stone(fields: &str) -> String {
+        format!(r#"{{"key": "minecraft:stone", "opaque": true, "has_texture": true, "texture_top": "stone",
+            "light_emission": 0, "natural": true, "max_height": 100, "min_height": 0, "rarity": 10{}}}"#, fields)
+    }
+
+    fn textures() -> Vec<String> {
+        ["bedrock", "dirt", "grass", "stone", "water_0", "water_1"].iter().map(|t| t.to_string()).collect()
+    }
+
+    fn problem_paths(contents: &str) -> Vec<String> {
+        match parse("fixture.json", contents, &textures()) {
+            Err(BlockRegistryError::Invalid { file, problems }) => {
+                assert_eq!(file, "fixture.json");
+                problems.into_iter().map(|p| p.path).collect()
+            },
+            Err(e) => panic!("expected validation problems, got {}", e),
+            Ok(_) => panic!("expected validation problems, fixture was accepted")
+        }
+    }
+
+    #[test]
+    fn accepts_valid_fixture() {
+        let list = parse("fixture.json", &fixture(&stone(""), ""), &textures()).unwrap();
+        assert_eq!(list.blocks.len(), 6);
+    }
+
+    #[test]
+    fn rejects_duplicate_key() {
+        let contents = fixture(&format!("{}, {}", stone(""), stone("")), "");
+        assert_eq!(problem_paths(&contents), vec!["blocks[6].key"]);
+    }
+
+    #[test]
+    fn rejects_bad_key_format() {
+        let contents = fixture(&stone("").replace("minecraft:stone", "Stone"), "");
+        assert_eq!(problem_paths(&contents), vec!["blocks[5].key"]);
+    }
+
+    #[test]
+    fn rejects_min_height_not_below_max_height() {
+        let contents = fixture(&stone("").replace(r#""min_height": 0"#, r#""min_height": 100"#), "");
+        assert_eq!(problem_paths(&contents), vec!["blocks[5].min_height"]);
+    }
+
+    #[test]
+    fn rejects_wrong_light_color_length() {
+        let contents = fixture(&stone(r#", "light_color": [1, 0.5]"#), "");
+        assert_eq!(problem_paths(&contents), vec!["blocks[5].light_color"]);
+    }
+
+    #[test]
+    fn rejects_missing_required_key() {
+        let contents = fixture("", "").replace("minecraft:sun", "minecraft:moon");
+        assert_eq!(problem_paths(&contents), vec!["blocks"]);
+    }
+
+    #[test]
+    fn rejects_missing_texture() {
+        let contents = fixture(&stone("").replace(r#""texture_top": "stone","#, ""), "");
+        assert_eq!(problem_paths(&contents), vec!["blocks[5].has_texture"]);
+    }
+
+    #[test]
+    fn rejects_bad_animations() {
+        let animations = r#"{"name": "empty", "frames": []},
+            {"name": "water", "frames": [{"texture": "water_0", "duration": 100}, {"texture": "water_1", "duration": 0}]}"#;
+        let contents = fixture("", animations);
+        assert_eq!(problem_paths(&contents), vec!["animations[0].frames", "animations[1].frames[1].duration"]);
+    }
+
+    #[test]
+    fn rejects_unknown_textures() {
+        let block = stone(r#", "texture_left": "water""#).replace(r#""texture_top": "stone""#, r#""texture_top": "stnoe""#);
+        let animations = r#"{"name": "water", "frames": [{"texture": "water_0", "duration": 100}, {"texture": "water_2", "duration": 100}]}"#;
+        let contents = fixture(&block, animations);
+        assert_eq!(problem_paths(&contents), vec!["blocks[5].texture_top", "animations[0].frames[1].texture"]);
+    }
+
+    #[test]
+    fn reports_malformed_json_position() {
+        match parse("fixture.json", "{\n  \"blocks\": [\n    {\"key\": }\n  ]\n}", &textures()) {
+            Err(BlockRegistryError::Parse { line, .. }) => assert_eq!(line, 3),
+            other => panic!("expected a parse error, got {:?}", other.map(|_| ()))
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,179 @@
+use math::{vec3, Vec3, Vec3i, Mat4, One, Ext::{rotate, translate}};
+use world::block::block::Block;
+use world::block::block_type::BlockType;
+
+const WALL_TILT: f32 = 22.5;
+const WALL_OFFSET: f32 = 0.4375;
+const WALL_HEIGHT: f32 = -0.28125;
+
+const FACINGS: [Facing; 6] = [Facing::North, Facing::East, Facing::South, Facing::West, Facing::Up, Facing::Down];
+const AXES: [Axis; 3] = [Axis::X, Axis::Y, Axis::Z];
+const HALVES: [Half; 2] = [Half::Bottom, Half::Top];
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum StateProperty {
+    Facing,
+    Axis,
+    Half
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum Placement {
+    Player,
+    Surface
+}
+
+impl Default for Placement {
+    fn default() -> Self { Placement::Player }
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum Facing {
+    North,
+    East,
+    South,
+    West,
+    Up,
+    Down
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum Axis {
+    X,
+    Y,
+    Z
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum Half {
+    Bottom,
+    Top
+}
+
+impl Facing {
+    pub fn from_normal(normal: &Vec3i) -> Facing {
+        match (normal.x, normal.y, normal.z) {
+            (1, _, _) => Facing::East,
+            (-1, _, _) => Facing::West,
+            (_, 1, _) => Facing::Up,
+            (_, -1, _) => Facing::Down,
+            (_, _, 1) => Facing::South,
+            _ => Facing::North
+        }
+    }
+
+    pub fn horizontal(direction: &Vec3) -> Facing {
+        if direction.x.abs() > direction.z.abs() {
+            if direction.x > 0.0 { Facing::East } else { Facing::West }
+        } else {
+            if direction.z > 0.0 { Facing::South } else { Facing::North }
+        }
+    }
+
+    pub fn opposite(&self) -> Facing {
+        match *self {
+            Facing::North => Facing::South,
+            Facing::East => Facing::West,
+            Facing::South => Facing::North,
+            Facing::West => Facing::East,
+            Facing::Up => Facing::Down,
+            Facing::Down => Facing::Up
+        }
+    }
+
+    fn yaw(&self) -> f32 {
+        match *self {
+            Facing::East => -90.0,
+            Facing::South => 180.0,
+            Facing::West => 90.0,
+            _ => 0.0
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct BlockState {
+    pub block_type: BlockType,
+    pub facing: Facing,
+    pub axis: Axis,
+    pub half: Half
+}
+
+impl BlockState {
+    pub fn new(block_type: BlockType) -> BlockState {
+        BlockState { block_type, facing: Facing::North, axis: Axis::Y, half: Half::Bottom }
+    }
+
+    pub fn air() -> BlockState {
+        BlockState::new(BlockType::AIR)
+    }
+
+    pub fn properties(&self) -> [u8; 3] {
+        [
+            FACINGS.iter().position(|&f| f == self.facing).unwrap() as u8,
+            AXES.iter().position(|&a| a == self.axis).unwrap() as u8,
+            HALVES.iter().position(|&h| h == self.half).unwrap() as u8
+        ]
+    }
+
+    pub fn from_properties(block_type: BlockType, properties: [u8; 3]) -> Option<BlockState> {
+        Some(BlockState {
+            block_type,
+            facing: *FACINGS.get(properties[0] as usize)?,
+            axis: *AXES.get(properties[1] as usize)?,
+            half: *HALVES.get(properties[2] as usize)?
+        })
+    }
+
+    pub fn placed(block: &Block, look: &Vec3, surface_normal: &Vec3i) -> BlockState {
+        let mut state = BlockState::new(block.m_type);
+        if block.has_property(StateProperty::Facing) {
+            state.facing = match block.placement {
+                Placement::Player => Facing::horizontal(look).opposite(),
+                Placement::Surface => Facing::from_normal(surface_normal)
+            };
+        }
+        if block.has_property(StateProperty::Axis) {
+            state.axis = match Facing::from_normal(surface_normal) {
+                Facing::East | Facing::West => Axis::X,
+                Facing::North | Facing::South => Axis::Z,
+                _ => Axis::Y
+            };
+        }
+        if block.has_property(StateProperty::Half) {
+            state.half = match surface_normal.y {
+                -1 => Half::Top,
+                _ => Half::Bottom
+            };
+        }
+        state
+    }
+
+    pub fn transform(&self, block: &Block) -> Mat4 {
+        let mut m = Mat4::one();
+        if block.has_property(StateProperty::Facing) {
+            m = match (block.placement, self.facing) {
+                (Placement::Surface, Facing::Up) => m,
+                (Placement::Surface, Facing::Down) => rotate(&m, 180f32.to_radians(), vec3(1.0, 0.0, 0.0)),
+                (Placement::Surface, facing) => {
+                    let m = rotate(&m, facing.yaw().to_radians(), vec3(0.0, 1.0, 0.0));
+                    let m = translate(&m, vec3(0.0, WALL_HEIGHT, WALL_OFFSET));
+                    let m = rotate(&m, -WALL_TILT.to_radians(), vec3(1.0, 0.0, 0.0));
+                    translate(&m, vec3(0.0, 0.5, 0.0))
+                },
+                (Placement::Player, facing) => rotate(&m, facing.yaw().to_radians(), vec3(0.0, 1.0, 0.0))
+            };
+        }
+        if block.has_property(StateProperty::Axis) {
+            m = match self.axis {
+                Axis::X => rotate(&m, -90f32.to_radians(), vec3(0.0, 0.0, 1.0)),
+                Axis::Y => m,
+                Axis::Z => rotate(&m, 90f32.to_radians(), vec3(1.0, 0.0, 0.0))
+            };
+        }
+        if block.has_property(StateProperty::Half) && self.half == Half::Top {
+            m = rotate(&m, 180f32.to_radians(), vec3(1.0, 0.0, 0.0));
+        }
+        m
+    }
+}
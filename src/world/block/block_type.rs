@@ -1,39 +1,27 @@
-use util::math::{Vec2, Vec3i, vec2, Zero};
+use util::math::{Vec2, Vec3i, Zero};
 use rand::{distributions::{Distribution, Standard}, Rng};
 use world::block::block_database;
 use util::noise;
 
-const IMG_WIDTH: f32 = 384.0;
-const IMG_HEIGHT: f32 = 784.0;
-const UV_WIDTH: f32 = 16.0 / IMG_WIDTH;
-const UV_HEIGHT: f32 = 16.0 / IMG_HEIGHT;
+pub mod keys {
+    pub const AIR: &'static str = "minecraft:air";
+    pub const BEDROCK: &'static str = "minecraft:bedrock";
+    pub const DIRT: &'static str = "minecraft:dirt";
+    pub const GRASS: &'static str = "minecraft:grass";
+    pub const SUN: &'static str = "minecraft:sun";
 
-#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Copy, Clone)]
-pub enum BlockType {
-    Air = 0,
-    Dirt,
-    Grass,
-    DiamondOre,
-    RedstoneOre,
-    GoldOre,
-    IronOre,
-    CoalOre,
-    Pumpkin,
-    JackOLantern,
-    Torch,
-    Bedrock,
-    Stone,
-    Gravel,
-    Granite,
-    Diorite,
-    Sun
+    pub const REQUIRED: [&'static str; 5] = [AIR, BEDROCK, DIRT, GRASS, SUN];
 }
 
+#[derive(Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd, Copy, Clone)]
+pub struct BlockType(pub u16);
+
 pub struct UvCoords {
     pub a: Vec2,
     pub b: Vec2,
     pub c: Vec2,
-    pub d: Vec2
+    pub d: Vec2,
+    pub layer: f32
 }
 
 impl Default for UvCoords {
@@ -42,51 +30,43 @@ impl Default for UvCoords {
             a: Zero::zero(),
             b: Zero::zero(),
             c: Zero::zero(),
-            d: Zero::zero()
+            d: Zero::zero(),
+            layer: 0.0
         }
     }
 }
 
 impl BlockType {
-    pub fn tex_coords(location: Vec2, scale_x: f32, scale_y: f32) -> UvCoords {
-        let x_max = (location.x + scale_x) * UV_WIDTH;
-        let y_max = location.y * UV_HEIGHT;
-
-        let x_min = location.x * UV_WIDTH;
-        let y_min = (location.y + scale_y) * UV_HEIGHT;
+    pub const AIR: BlockType = BlockType(0);
 
-        UvCoords {
-            a: vec2(x_min, y_min),
-            b: vec2(x_max, y_min),
-            c: vec2(x_max, y_max),
-            d: vec2(x_min, y_max)
-        }
+    pub fn id(&self) -> u16 {
+        self.0
     }
 
     pub fn noise_natural(pos: Vec3i) -> BlockType {
-        use self::BlockType::*;
-        let mut blocks = block_database::get().blocks_at_height(pos.y);
+        let database = block_database::get();
+        let blocks = database.blocks_at_height(pos.y);
         let len = blocks.len();
 
         let index = noise::noise_3i(pos, len);
-        blocks.get(index).unwrap_or(&BlockType::Air).clone()
+        blocks.get(index).cloned().unwrap_or(BlockType::AIR)
     }
 
     pub fn random_natural() -> BlockType {
-        use self::BlockType::*;
-        let mut types = block_database::get().natural_blocks().keys();
+        let database = block_database::get();
+        let mut types = database.natural_blocks().keys();
         let len = types.len();
 
         types.nth(::rand::thread_rng().gen_range(0, len))
-            .unwrap_or(&BlockType::Air).clone()
+            .cloned().unwrap_or(BlockType::AIR)
     }
 
     pub fn random_unnatural() -> BlockType {
-        use self::BlockType::*;
-        let mut types = block_database::get().unnatural_blocks().keys();
+        let database = block_database::get();
+        let mut types = database.unnatural_blocks().keys();
         let len = types.len();
 
         types.nth(::rand::thread_rng().gen_range(0, len))
-            .unwrap_or(&BlockType::Air).clone()
+            .cloned().unwrap_or(BlockType::AIR)
     }
 }
\ No newline at end of file
@@ -1,6 +1,9 @@
 pub mod block;
 pub mod block_type;
+pub mod block_state;
+pub mod block_model;
 pub mod block_database;
-pub mod block_texture;
-pub mod block_buffer;
+pub mod block_registry;
+pub mod block_id_map;
+pub mod texture_array;
 pub mod block_light;
\ No newline at end of file
@@ -0,0 +1,208 @@
+use png;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use math::vec2;
+use world::block::block_type::UvCoords;
+use world::block::block_database::{self, BlockDatabase};
+use util::resources;
+
+const TEXTURE_DIRECTORY: &'static str = "assets/textures/blocks";
+
+lazy_static! {
+    static ref TEXTURE_ARRAY: RwLock<Arc<TextureArray>> = RwLock::new(Arc::new(
+        TextureArray::load().unwrap_or_else(|e| panic!("Failed to build block texture array: {}", e))
+    ));
+}
+
+pub fn texture_names() -> Vec<String> {
+    resources::get().list(TEXTURE_DIRECTORY, "png").iter()
+        .filter_map(|path| Path::new(path).file_stem().and_then(|s| s.to_str()).map(|s| s.to_string()))
+        .collect()
+}
+
+pub fn get() -> Arc<TextureArray> {
+    TEXTURE_ARRAY.read().unwrap().clone()
+}
+
+pub fn replace(array: TextureArray) {
+    *TEXTURE_ARRAY.write().unwrap() = Arc::new(array);
+}
+
+struct Image {
+    name: String,
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnimationFrame {
+    pub texture: String,
+    pub duration: u64
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TextureAnimation {
+    pub name: String,
+    pub frames: Vec<AnimationFrame>
+}
+
+pub struct AnimatedLayer {
+    pub layer: u32,
+    frames: Vec<(u32, u64)>,
+    period: u64
+}
+
+impl AnimatedLayer {
+    pub fn frame_at(&self, time: u64) -> u32 {
+        let mut remaining = time % self.period;
+        for &(layer, duration) in &self.frames {
+            if remaining < duration { return layer; }
+            remaining -= duration;
+        }
+        self.frames[0].0
+    }
+
+    pub fn frame_layers(&self) -> Vec<u32> {
+        self.frames.iter().map(|&(layer, _)| layer).collect()
+    }
+}
+
+pub struct TextureArray {
+    tile_size: (u32, u32),
+    pixels: Vec<u8>,
+    layers: HashMap<String, u32>,
+    animated: Vec<AnimatedLayer>
+}
+
+impl TextureArray {
+    fn load() -> Result<TextureArray, String> {
+        TextureArray::load_for(&block_database::get())
+    }
+
+    pub fn load_for(database: &BlockDatabase) -> Result<TextureArray, String> {
+        TextureArray::build(TEXTURE_DIRECTORY, database.animations())
+    }
+
+    pub fn build(directory: &str, animations: &[TextureAnimation]) -> Result<TextureArray, String> {
+        let mut images = load_images(directory)?;
+        if images.is_empty() {
+            return Err(format!("No textures found in {}", directory));
+        }
+        images.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let tile_size = (images[0].width, images[0].height);
+        let mut pixels = Vec::with_capacity(images.len() * (tile_size.0 * tile_size.1 * 4) as usize);
+        let mut layers = HashMap::new();
+        for (layer, image) in images.into_iter().enumerate() {
+            if (image.width, image.height) != tile_size {
+                return Err(format!("Texture {} is {}x{}, expected {}x{}",
+                                   image.name, image.width, image.height, tile_size.0, tile_size.1));
+            }
+            pixels.extend_from_slice(&image.pixels);
+            layers.insert(image.name, layer as u32);
+        }
+
+        let mut array = TextureArray { tile_size, pixels, layers, animated: Vec::new() };
+        for animation in animations {
+            array.add_animation(animation)?;
+        }
+        Ok(array)
+    }
+
+    fn add_animation(&mut self, animation: &TextureAnimation) -> Result<(), String> {
+        if self.layers.contains_key(&animation.name) {
+            return Err(format!("Animation {} shadows an existing texture", animation.name));
+        }
+        let mut frames = Vec::with_capacity(animation.frames.len());
+        for frame in &animation.frames {
+            let layer = *self.layers.get(&frame.texture)
+                .ok_or(format!("Animation {} references unknown texture {}", animation.name, frame.texture))?;
+            if frame.duration == 0 {
+                return Err(format!("Animation {} has a frame with zero duration", animation.name));
+            }
+            frames.push((layer, frame.duration));
+        }
+        if frames.is_empty() {
+            return Err(format!("Animation {} has no frames", animation.name));
+        }
+
+        let layer = self.layer_count();
+        let first_frame = self.layer_pixels(frames[0].0).to_vec();
+        self.pixels.extend_from_slice(&first_frame);
+        self.layers.insert(animation.name.clone(), layer);
+        self.animated.push(AnimatedLayer {
+            layer,
+            period: frames.iter().map(|&(_, duration)| duration).sum(),
+            frames
+        });
+        Ok(())
+    }
+
+    pub fn tile_size(&self) -> (u32, u32) {
+        self.tile_size
+    }
+
+    pub fn layer_count(&self) -> u32 {
+        self.layers.len() as u32
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn layer_pixels(&self, layer: u32) -> &[u8] {
+        let size = (self.tile_size.0 * self.tile_size.1 * 4) as usize;
+        &self.pixels[layer as usize * size..(layer as usize + 1) * size]
+    }
+
+    pub fn animated_layers(&self) -> &[AnimatedLayer] {
+        &self.animated
+    }
+
+    pub fn layer(&self, name: &str) -> f32 {
+        match self.layers.get(name) {
+            Some(&layer) => layer as f32,
+            None => {
+                println!("Unknown block texture: {}", name);
+                0.0
+            }
+        }
+    }
+
+    pub fn tex_coords(&self, name: &str) -> UvCoords {
+        UvCoords {
+            a: vec2(0.0, 1.0),
+            b: vec2(1.0, 1.0),
+            c: vec2(1.0, 0.0),
+            d: vec2(0.0, 0.0),
+            layer: self.layer(name)
+        }
+    }
+}
+
+fn load_images(directory: &str) -> Result<Vec<Image>, String> {
+    resources::get().list(directory, "png").iter().map(|path| load_image(path)).collect()
+}
+
+fn load_image(path: &str) -> Result<Image, String> {
+    let name = Path::new(path).file_stem().and_then(|s| s.to_str())
+        .ok_or(format!("Invalid texture name: {}", path))?
+        .to_string();
+    let bytes = resources::get().read(path)?;
+    let (info, mut reader) = png::Decoder::new(&bytes[..]).read_info()
+        .map_err(|e| format!("Failed to read png header {}: {}", path, e))?;
+    let mut data = vec![0; info.buffer_size()];
+    reader.next_frame(&mut data)
+        .map_err(|e| format!("Failed to read png data {}: {}", path, e))?;
+
+    let pixels = match info.color_type {
+        png::ColorType::RGBA => data,
+        png::ColorType::RGB => data.chunks(3).flat_map(|p| vec![p[0], p[1], p[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => data.chunks(2).flat_map(|p| vec![p[0], p[0], p[0], p[1]]).collect(),
+        png::ColorType::Grayscale => data.iter().flat_map(|&p| vec![p, p, p, 255]).collect(),
+        png::ColorType::Indexed => return Err(format!("Unsupported indexed png: {}", path))
+    };
+    Ok(Image { name, width: info.width, height: info.height, pixels })
+}
@@ -0,0 +1,129 @@
+use world::block::block_state::BlockState;
+use world::block::block_type::BlockType;
+use world::constants::{CHUNK_SIZE, CHUNK_AREA, CHUNK_BOTTOM};
+
+const MIN_Y: i32 = CHUNK_BOTTOM - 1;
+pub const MAX_LIGHT: u8 = 15;
+
+#[derive(Debug, Clone)]
+pub struct BlockStorage {
+    palette: Vec<BlockState>,
+    indices: Vec<u16>,
+    light: Vec<u8>,
+    layers: i32
+}
+
+impl BlockStorage {
+    pub fn new() -> BlockStorage {
+        BlockStorage { palette: vec![BlockState::air()], indices: Vec::new(), light: Vec::new(), layers: 0 }
+    }
+
+    pub fn from_parts(palette: Vec<BlockState>, indices: Vec<u16>) -> Result<BlockStorage, String> {
+        if palette.is_empty() || palette[0] != BlockState::air() {
+            return Err("Block palette must start with air".to_string());
+        }
+        if indices.len() % CHUNK_AREA as usize != 0 {
+            return Err(format!("Block count {} is not a whole number of layers", indices.len()));
+        }
+        if let Some(&i) = indices.iter().find(|&&i| i as usize >= palette.len()) {
+            return Err(format!("Palette index {} out of range for {} entries", i, palette.len()));
+        }
+        let layers = (indices.len() / CHUNK_AREA as usize) as i32;
+        Ok(BlockStorage { palette, indices, light: Vec::new(), layers })
+    }
+
+    pub fn with_light(mut self, light: Vec<u8>) -> Result<BlockStorage, String> {
+        if !light.is_empty() && light.len() != self.indices.len() {
+            return Err(format!("Light count {} does not match block count {}", light.len(), self.indices.len()));
+        }
+        if let Some(&level) = light.iter().find(|&&level| level > MAX_LIGHT) {
+            return Err(format!("Light level {} is above {}", level, MAX_LIGHT));
+        }
+        self.light = light;
+        Ok(self)
+    }
+
+    pub fn palette(&self) -> &[BlockState] {
+        &self.palette
+    }
+
+    pub fn indices(&self) -> &[u16] {
+        &self.indices
+    }
+
+    pub fn light_levels(&self) -> &[u8] {
+        &self.light
+    }
+
+    pub fn light(&self, x: i32, y: i32, z: i32) -> u8 {
+        match self.index(x, y, z) {
+            Some(i) if !self.light.is_empty() => self.light[i],
+            _ => 0
+        }
+    }
+
+    pub fn set_light(&mut self, x: i32, y: i32, z: i32, level: u8) {
+        if let Some(i) = self.index(x, y, z) {
+            if self.light.is_empty() {
+                if level == 0 { return; }
+                self.light.resize(self.indices.len(), 0);
+            }
+            self.light[i] = level.min(MAX_LIGHT);
+        }
+    }
+
+    pub fn get(&self, x: i32, y: i32, z: i32) -> BlockState {
+        match self.index(x, y, z) {
+            Some(i) => self.palette[self.indices[i] as usize],
+            None => BlockState::air()
+        }
+    }
+
+    pub fn get_type(&self, x: i32, y: i32, z: i32) -> BlockType {
+        self.get(x, y, z).block_type
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, z: i32, state: BlockState) {
+        if x < 0 || x >= CHUNK_SIZE || z < 0 || z >= CHUNK_SIZE || y < MIN_Y { return; }
+        if y - MIN_Y >= self.layers {
+            if state.block_type == BlockType::AIR { return; }
+            self.layers = y - MIN_Y + 1;
+            self.indices.resize((self.layers * CHUNK_AREA) as usize, 0);
+            if !self.light.is_empty() {
+                self.light.resize(self.indices.len(), 0);
+            }
+        }
+        let entry = self.palette_entry(state);
+        let i = self.index(x, y, z).unwrap();
+        self.indices[i] = entry;
+    }
+
+    pub fn max_y(&self) -> i32 {
+        MIN_Y + self.layers
+    }
+
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = ((i32, i32, i32), BlockState)> + 'a {
+        self.indices.iter().enumerate()
+            .filter(move |&(_, &entry)| self.palette[entry as usize].block_type != BlockType::AIR)
+            .map(move |(i, &entry)| {
+                let i = i as i32;
+                ((i % CHUNK_SIZE, i / CHUNK_AREA + MIN_Y, (i / CHUNK_SIZE) % CHUNK_SIZE), self.palette[entry as usize])
+            })
+    }
+
+    fn palette_entry(&mut self, state: BlockState) -> u16 {
+        match self.palette.iter().position(|s| *s == state) {
+            Some(i) => i as u16,
+            None => {
+                self.palette.push(state);
+                (self.palette.len() - 1) as u16
+            }
+        }
+    }
+
+    fn index(&self, x: i32, y: i32, z: i32) -> Option<usize> {
+        if x < 0 || x >= CHUNK_SIZE || z < 0 || z >= CHUNK_SIZE { return None; }
+        if y < MIN_Y || y >= MIN_Y + self.layers { return None; }
+        Some(((y - MIN_Y) * CHUNK_AREA + z * CHUNK_SIZE + x) as usize)
+    }
+}
@@ -1,31 +1,32 @@
 use math::{vec3, Vec3, Vec3i, Mat4, One, Ext::translate};
-use std::collections::HashMap;
-use world::chunk::chunk_mesh::ChunkMesh;
-use world::block::block_type::BlockType;
+use std::sync::Arc;
+use world::chunk::block_storage::BlockStorage;
+use world::block::block_state::BlockState;
+use world::block::block_type::{BlockType, keys};
+use world::block::block_database;
 use world::constants::CHUNK_SIZE;
-use world::constants::CHUNK_HEIGHT;
-use util::vertex::Vertex;
-use util::timer::StopWatch;
 use util::noise::height;
 
 pub struct Chunk {
-    mesh: ChunkMesh,
     world_position: Vec3i,
     index_position: Vec3i,
-    block_map: HashMap<(i32, i32, i32), BlockType>
+    blocks: Arc<BlockStorage>
 }
 
 impl Chunk {
     pub fn new(index_position: Vec3i) -> Chunk {
-        let mut block_map: HashMap<(i32, i32, i32), BlockType> = HashMap::new();
+        let mut blocks = BlockStorage::new();
         let world_position = Vec3i::new(index_position.x * CHUNK_SIZE, 0, index_position.z * CHUNK_SIZE);
+        let database = block_database::get();
+        let block_type = |key: &str| database.block_type(key).unwrap_or(BlockType::AIR);
+        let (bedrock, dirt, grass) = (block_type(keys::BEDROCK), block_type(keys::DIRT), block_type(keys::GRASS));
 
         for x in 0..CHUNK_SIZE {
         for z in 0..CHUNK_SIZE {
             let mut highest_dirt = -1;
             for y in -1..(height(world_position.x + x, world_position.z + z) as i32) {
                 if y == -1 {
-                    block_map.insert((x, y, z), BlockType::Bedrock);
+                    blocks.set(x, y, z, BlockState::new(bedrock));
                     continue;
                 }
                 let block_type = BlockType::noise_natural(Vec3i::new(
@@ -33,31 +34,45 @@ impl Chunk {
                     y,
                     z + (index_position.z * CHUNK_SIZE)
                 ));
-                if block_type == BlockType::Dirt { highest_dirt = y; }
-                block_map.insert((x,y,z), block_type);
+                if block_type == dirt { highest_dirt = y; }
+                blocks.set(x, y, z, BlockState::new(block_type));
             }
             if highest_dirt > 0 {
-                block_map.entry((x, highest_dirt, z)).and_modify(|b| *b = BlockType::Grass);
+                blocks.set(x, highest_dirt, z, BlockState::new(grass));
             }
         }}
 
 
+        Chunk::from_blocks(index_position, Arc::new(blocks))
+    }
+
+    pub fn from_blocks(index_position: Vec3i, blocks: Arc<BlockStorage>) -> Chunk {
+        let world_position = Vec3i::new(index_position.x * CHUNK_SIZE, 0, index_position.z * CHUNK_SIZE);
         Chunk {
-            mesh: ChunkMesh::new(&block_map),
             world_position,
             index_position,
-            block_map
+            blocks
         }
     }
 
+    pub fn blocks(&self) -> Arc<BlockStorage> {
+        self.blocks.clone()
+    }
+
+    pub fn block(&self, x: i32, y: i32, z: i32) -> BlockState {
+        self.blocks.get(x, y, z)
+    }
+
+    pub fn set_block(&mut self, x: i32, y: i32, z: i32, state: BlockState) {
+        Arc::make_mut(&mut self.blocks).set(x, y, z, state);
+    }
+
     pub fn get_highest_block(&self, pos_xz: (i32, i32)) -> i32 {
         let (x, z) = pos_xz;
         let mut highest_block = 0;
-        for y in 0..CHUNK_HEIGHT {
-            if let Some(block_type) = self.block_map.get(&(x, y, z)) {
-                if block_type == &BlockType::Air { continue; }
-                highest_block = y;
-            }
+        for y in 0..self.blocks.max_y() {
+            if self.blocks.get_type(x, y, z) == BlockType::AIR { continue; }
+            highest_block = y;
         }
         highest_block
     }
@@ -66,14 +81,6 @@ impl Chunk {
         (self.index_position.x, self.index_position.z)
     }
 
-    pub fn verts(&self) -> &Vec<Vertex> {
-        self.mesh.verts()
-    }
-
-    pub fn inds(&self) -> &Vec<u32> {
-        self.mesh.inds()
-    }
-
     pub fn model(&self) -> Mat4 {
         translate(&Mat4::one(), vec3(
             self.world_position.x as f32,
@@ -81,4 +88,20 @@ impl Chunk {
             self.world_position.z as f32)
         )
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::noise::{set_seed, DEFAULT_SEED};
+
+    #[test]
+    fn generation_is_deterministic_for_a_seed() {
+        set_seed(DEFAULT_SEED);
+        let first = Chunk::new(Vec3i::new(3, 0, -2));
+        let second = Chunk::new(Vec3i::new(3, 0, -2));
+        assert_eq!(first.blocks().palette(), second.blocks().palette());
+        assert_eq!(first.blocks().indices(), second.blocks().indices());
+        assert!(first.blocks().iter().next().is_some());
+    }
 }
\ No newline at end of file
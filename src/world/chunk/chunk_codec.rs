@@ -0,0 +1,316 @@
+use flate2::Compression as ZlibLevel;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use std::io::{Read, Write};
+use world::block::block_database;
+use world::block::block_id_map::BlockIdMap;
+use world::block::block_state::BlockState;
+use world::chunk::block_storage::BlockStorage;
+use world::constants::CHUNK_AREA;
+
+pub const CHUNK_FORMAT_VERSION: u8 = 2;
+const HEADER_SIZE: usize = 6;
+const MAX_BODY_SIZE: usize = 1 << 24;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Compression {
+    None,
+    Zlib
+}
+
+pub fn encode(blocks: &BlockStorage, ids: &mut BlockIdMap, compression: Compression) -> Vec<u8> {
+    let database = block_database::get();
+    let mut body = Vec::new();
+    body.extend_from_slice(&(blocks.palette().len() as u16).to_be_bytes());
+    for state in blocks.palette() {
+        body.extend_from_slice(&ids.saved_id(state.block_type, &database).to_be_bytes());
+        body.extend_from_slice(&state.properties());
+    }
+
+    let layers = blocks.indices().len() / CHUNK_AREA as usize;
+    let bits = bits_per_index(blocks.palette().len());
+    body.extend_from_slice(&(layers as u16).to_be_bytes());
+    body.push(bits);
+    for word in pack(blocks.indices(), bits) {
+        body.extend_from_slice(&word.to_be_bytes());
+    }
+    if blocks.light_levels().is_empty() {
+        body.push(0);
+    } else {
+        body.push(1);
+        body.extend(blocks.light_levels().chunks(2).map(|pair| pair[0] | pair.get(1).cloned().unwrap_or(0) << 4));
+    }
+
+    let mut data = vec![CHUNK_FORMAT_VERSION, 0];
+    data.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    match compression {
+        Compression::None => data.extend_from_slice(&body),
+        Compression::Zlib => {
+            data[1] = 1;
+            let mut encoder = ZlibEncoder::new(data, ZlibLevel::default());
+            encoder.write_all(&body).unwrap();
+            data = encoder.finish().unwrap();
+        }
+    }
+    data
+}
+
+pub fn decode(data: &[u8], ids: &BlockIdMap) -> Result<BlockStorage, String> {
+    if data.len() < HEADER_SIZE {
+        return Err("Chunk data is shorter than its header".to_string());
+    }
+    if data[0] == 0 || data[0] > CHUNK_FORMAT_VERSION {
+        return Err(format!("Unsupported chunk format version {}", data[0]));
+    }
+    let length = u32::from_be_bytes([data[2], data[3], data[4], data[5]]) as usize;
+    if length > MAX_BODY_SIZE {
+        return Err(format!("Chunk body of {} bytes is too large", length));
+    }
+    let body = match data[1] {
+        0 => data[HEADER_SIZE..].to_vec(),
+        1 => {
+            let mut body = Vec::with_capacity(length);
+            ZlibDecoder::new(&data[HEADER_SIZE..]).take(length as u64 + 1).read_to_end(&mut body)
+                .map_err(|e| format!("Failed to decompress chunk: {}", e))?;
+            body
+        },
+        c => return Err(format!("Unknown chunk compression {}", c))
+    };
+    if body.len() != length {
+        return Err(format!("Chunk body is {} bytes, header says {}", body.len(), length));
+    }
+
+    let mut r = Reader { data: &body, position: 0 };
+    let blocks = decode_blocks(&mut r, ids)?;
+    let light = if data[0] >= 2 { decode_light(&mut r, blocks.indices().len())? } else { Vec::new() };
+    if r.position != body.len() {
+        return Err(format!("{} trailing bytes after chunk", body.len() - r.position));
+    }
+    blocks.with_light(light)
+}
+
+fn decode_blocks(r: &mut Reader, ids: &BlockIdMap) -> Result<BlockStorage, String> {
+    let database = block_database::get();
+    let mut palette = Vec::new();
+    for _ in 0..r.u16()? {
+        let id = r.u16()?;
+        if id as usize >= ids.keys().len() {
+            return Err(format!("Block id {} is not in the block id map of {} entries", id, ids.keys().len()));
+        }
+        let block_type = ids.block_type(id, &database);
+        let properties = r.bytes(3)?;
+        palette.push(BlockState::from_properties(block_type, [properties[0], properties[1], properties[2]])
+            .ok_or_else(|| format!("Invalid block properties {:?}", properties))?);
+    }
+
+    let count = r.u16()? as usize * CHUNK_AREA as usize;
+    let bits = r.bytes(1)?[0];
+    if bits != bits_per_index(palette.len()) {
+        return Err(format!("Expected {} bits per index for {} palette entries, got {}",
+                           bits_per_index(palette.len()), palette.len(), bits));
+    }
+    let mut words = Vec::new();
+    for _ in 0..word_count(count, bits) {
+        let b = r.bytes(8)?;
+        words.push(u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]));
+    }
+    BlockStorage::from_parts(palette, unpack(&words, bits, count))
+}
+
+fn decode_light(r: &mut Reader, count: usize) -> Result<Vec<u8>, String> {
+    match r.bytes(1)?[0] {
+        0 => Ok(Vec::new()),
+        1 => Ok(r.bytes((count + 1) / 2)?.iter().flat_map(|&b| vec![b & 0xf, b >> 4]).take(count).collect()),
+        flag => Err(format!("Unknown light flag {}", flag))
+    }
+}
+
+fn bits_per_index(palette_length: usize) -> u8 {
+    let mut bits = 0;
+    while (1usize << bits) < palette_length {
+        bits += 1;
+    }
+    bits
+}
+
+fn word_count(count: usize, bits: u8) -> usize {
+    if bits == 0 { return 0; }
+    let per_word = 64 / bits as usize;
+    (count + per_word - 1) / per_word
+}
+
+fn pack(indices: &[u16], bits: u8) -> Vec<u64> {
+    if bits == 0 { return Vec::new(); }
+    let per_word = 64 / bits as usize;
+    indices.chunks(per_word).map(|chunk| {
+        chunk.iter().enumerate().fold(0u64, |word, (i, &index)| word | (index as u64) << (i * bits as usize))
+    }).collect()
+}
+
+fn unpack(words: &[u64], bits: u8, count: usize) -> Vec<u16> {
+    if bits == 0 { return vec![0; count]; }
+    let per_word = 64 / bits as usize;
+    let mask = (1u64 << bits) - 1;
+    (0..count).map(|i| ((words[i / per_word] >> ((i % per_word) * bits as usize)) & mask) as u16).collect()
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+        if self.data.len() - self.position < count {
+            return Err("Chunk data ended unexpectedly".to_string());
+        }
+        let bytes = &self.data[self.position..self.position + count];
+        self.position += count;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use world::block::block_type::BlockType;
+    use world::chunk::block_storage::MAX_LIGHT;
+    use world::constants::CHUNK_SIZE;
+
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self, bound: usize) -> usize {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((self.0 >> 33) as usize) % bound
+        }
+    }
+
+    fn block_types() -> Vec<BlockType> {
+        let database = block_database::get();
+        let mut types: Vec<BlockType> = database.natural_blocks().keys()
+            .chain(database.unnatural_blocks().keys()).cloned().filter(|&t| t != BlockType::AIR).collect();
+        types.sort_by_key(|t| t.id());
+        types
+    }
+
+    fn random_storage(rng: &mut Lcg, types: &[BlockType], palette_size: usize, layers: i32, light: bool) -> BlockStorage {
+        let palette: Vec<BlockState> = (0..palette_size).map(|_| {
+            let block_type = types[rng.next(types.len())];
+            BlockState::from_properties(block_type, [rng.next(6) as u8, rng.next(3) as u8, rng.next(2) as u8]).unwrap()
+        }).collect();
+        let mut blocks = BlockStorage::new();
+        for y in -1..(layers - 1) {
+            for z in 0..CHUNK_SIZE {
+                for x in 0..CHUNK_SIZE {
+                    blocks.set(x, y, z, palette[rng.next(palette.len())]);
+                    if light {
+                        blocks.set_light(x, y, z, rng.next(MAX_LIGHT as usize + 1) as u8);
+                    }
+                }
+            }
+        }
+        blocks
+    }
+
+    fn assert_round_trip(blocks: &BlockStorage, compression: Compression) {
+        let mut ids = BlockIdMap::new();
+        let data = encode(blocks, &mut ids, compression);
+        assert_eq!(data[0], CHUNK_FORMAT_VERSION);
+        assert_eq!(data[1], if compression == Compression::Zlib { 1 } else { 0 });
+        let decoded = decode(&data, &BlockIdMap::from_keys(ids.keys().to_vec())).unwrap();
+        assert_eq!(decoded.max_y(), blocks.max_y());
+        assert_eq!(decoded.light_levels(), blocks.light_levels());
+        for ((x, y, z), state) in blocks.iter() {
+            assert_eq!(decoded.get(x, y, z), state);
+        }
+        assert_eq!(decoded.iter().count(), blocks.iter().count());
+    }
+
+    #[test]
+    fn round_trips_random_chunks() {
+        let types = block_types();
+        let mut rng = Lcg(0x5eed);
+        for i in 0..24 {
+            let palette_size = 1 + rng.next(300);
+            let layers = rng.next(40) as i32;
+            let blocks = random_storage(&mut rng, &types, palette_size, layers, i % 2 == 0);
+            assert_round_trip(&blocks, if i % 3 == 0 { Compression::None } else { Compression::Zlib });
+        }
+    }
+
+    #[test]
+    fn round_trips_empty_and_single_bit_chunks() {
+        assert_round_trip(&BlockStorage::new(), Compression::None);
+        assert_round_trip(&BlockStorage::new(), Compression::Zlib);
+
+        let mut blocks = BlockStorage::new();
+        blocks.set(3, 5, 7, BlockState::new(block_types()[0]));
+        blocks.set_light(3, 6, 7, MAX_LIGHT);
+        assert_eq!(bits_per_index(blocks.palette().len()), 1);
+        assert_round_trip(&blocks, Compression::None);
+        assert_round_trip(&blocks, Compression::Zlib);
+    }
+
+    #[test]
+    fn packs_one_and_sixteen_bit_indices() {
+        let mut rng = Lcg(42);
+        for &bits in [1u8, 16].iter() {
+            let count = CHUNK_AREA as usize * 3 + 5;
+            let indices: Vec<u16> = (0..count).map(|_| rng.next(1 << bits) as u16).collect();
+            let words = pack(&indices, bits);
+            assert_eq!(words.len(), word_count(count, bits));
+            assert_eq!(unpack(&words, bits, count), indices);
+        }
+        assert_eq!(bits_per_index(2), 1);
+        assert_eq!(bits_per_index(1 << 16), 16);
+    }
+
+    #[test]
+    fn decodes_version_one_chunks() {
+        let mut blocks = BlockStorage::new();
+        blocks.set(1, 2, 3, BlockState::new(block_types()[0]));
+        let mut ids = BlockIdMap::new();
+        let mut data = encode(&blocks, &mut ids, Compression::None);
+        assert_eq!(data.pop(), Some(0));
+        let length = (data.len() - HEADER_SIZE) as u32;
+        data[0] = 1;
+        data[2..HEADER_SIZE].copy_from_slice(&length.to_be_bytes());
+
+        let decoded = decode(&data, &ids).unwrap();
+        assert_eq!(decoded.get(1, 2, 3), blocks.get(1, 2, 3));
+        assert!(decoded.light_levels().is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_headers() {
+        let ids = BlockIdMap::new();
+        let data = encode(&BlockStorage::new(), &mut BlockIdMap::new(), Compression::None);
+
+        assert!(decode(&data[..HEADER_SIZE - 1], &ids).unwrap_err().contains("shorter than its header"));
+
+        let mut oversized = data.clone();
+        oversized[2..HEADER_SIZE].copy_from_slice(&(MAX_BODY_SIZE as u32 + 1).to_be_bytes());
+        assert!(decode(&oversized, &ids).unwrap_err().contains("too large"));
+
+        let mut unknown = data.clone();
+        unknown[0] = CHUNK_FORMAT_VERSION + 1;
+        assert!(decode(&unknown, &ids).unwrap_err().contains("Unsupported chunk format version"));
+
+        let mut truncated = data.clone();
+        truncated.pop();
+        assert!(decode(&truncated, &ids).is_err());
+
+        let mut blocks = BlockStorage::new();
+        blocks.set(0, 0, 0, BlockState::new(block_types()[0]));
+        let mut saved_ids = BlockIdMap::new();
+        let data = encode(&blocks, &mut saved_ids, Compression::Zlib);
+        assert!(decode(&data, &ids).unwrap_err().contains("not in the block id map"));
+        assert!(decode(&data, &saved_ids).is_ok());
+    }
+}
\ No newline at end of file
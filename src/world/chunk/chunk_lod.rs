@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+use world::block::block_state::BlockState;
+use world::block::block_type::BlockType;
+use world::chunk::block_storage::BlockStorage;
+use world::constants::{CHUNK_SIZE, LOD_DISTANCES};
+
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum LodLevel {
+    Full,
+    Half,
+    Quarter,
+    Eighth
+}
+
+impl LodLevel {
+    pub fn scale(&self) -> i32 {
+        match *self {
+            LodLevel::Full => 1,
+            LodLevel::Half => 2,
+            LodLevel::Quarter => 4,
+            LodLevel::Eighth => 8
+        }
+    }
+
+    pub fn for_distance(chunk_distance: i32) -> LodLevel {
+        if chunk_distance < LOD_DISTANCES[0] { LodLevel::Full }
+        else if chunk_distance < LOD_DISTANCES[1] { LodLevel::Half }
+        else if chunk_distance < LOD_DISTANCES[2] { LodLevel::Quarter }
+        else { LodLevel::Eighth }
+    }
+}
+
+pub fn downsample(blocks: &BlockStorage, lod: LodLevel) -> BlockStorage {
+    let s = lod.scale();
+    let mut cells: HashMap<(i32, i32, i32), HashMap<BlockType, i32>> = HashMap::new();
+
+    for ((x, y, z), state) in blocks.iter() {
+        let cell = (cell_index(x, s), cell_index(y, s), cell_index(z, s));
+        *cells.entry(cell).or_insert(HashMap::new())
+            .entry(state.block_type).or_insert(0) += 1;
+    }
+
+    let mut downsampled = BlockStorage::new();
+    for ((cx, cy, cz), counts) in cells {
+        let width = (CHUNK_SIZE - cx * s).min(s);
+        let depth = (CHUNK_SIZE - cz * s).min(s);
+        let solid: i32 = counts.values().sum();
+        if solid * 2 < width * s * depth { continue; }
+
+        if let Some((block_type, _)) = counts.into_iter().max_by_key(|&(block_type, count)| (count, block_type.id())) {
+            downsampled.set(cx, cy, cz, BlockState::new(block_type));
+        }
+    }
+    downsampled
+}
+
+fn cell_index(v: i32, scale: i32) -> i32 {
+    if v < 0 { (v - scale + 1) / scale } else { v / scale }
+}
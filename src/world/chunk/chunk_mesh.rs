@@ -1,9 +1,10 @@
 use util::vertex::Vertex;
-use world::block::block::Block;
-use std::collections::HashMap;
-use math::{Vec3i, Mat4, vec3, Ext::{translate}, One};
-use world::block::block_type::BlockType;
+use world::block::block::Face;
+use math::{Vec3i, Mat4, vec3, vec4, Ext::{translate}, One};
 use world::block::block_database;
+use world::chunk::block_storage::BlockStorage;
+use world::chunk::chunk_lod::{self, LodLevel};
+use world::constants::CHUNK_SIZE;
 
 pub struct ChunkMesh {
     vertices: Vec<Vertex>,
@@ -11,67 +12,44 @@ pub struct ChunkMesh {
 }
 
 impl ChunkMesh {
-    pub fn new(block_positions: &HashMap<(i32, i32, i32), BlockType>) -> ChunkMesh {
-        let mut vertices: Vec<Vertex> = Vec::new();
-        let mut indices: Vec<u32> = Vec::new();
-        let mut index_stride = 4;
-        let mut current_stride = 0;
+    pub fn new(blocks: &BlockStorage, lod: LodLevel) -> ChunkMesh {
+        if lod == LodLevel::Full {
+            return ChunkMesh::build(blocks);
+        }
 
-        for ((x, y, z), block_type) in block_positions.iter() {
-            let (x, y, z) = (x.clone(), y.clone(), z.clone());
+        // Border faces are always emitted, so height differences between LOD rings are walled off instead of leaving cracks
+        let mut mesh = ChunkMesh::build(&chunk_lod::downsample(blocks, lod));
+        let s = lod.scale() as f32;
+        let edge = CHUNK_SIZE as f32 - 0.5;
+        for v in mesh.vertices.iter_mut() {
+            v.position = (v.position + vec3(0.5, 0.5, 0.5)) * s - vec3(0.5, 0.5, 0.5);
+            v.position.x = v.position.x.min(edge);
+            v.position.z = v.position.z.min(edge);
+        }
+        mesh
+    }
 
-            let block = block_database::get().get_block(block_type.clone());
-            if block.m_type == BlockType::Air { continue; }
+    fn build(blocks: &BlockStorage) -> ChunkMesh {
+        let mut vertices: Vec<Vertex> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+        let database = block_database::get();
 
-            let right = block_database::get().unwrap_block(block_positions.get(&(x + 1, y, z)));
-            let left = block_database::get().unwrap_block(block_positions.get(&(x - 1, y, z)));
-            let top = block_database::get().unwrap_block(block_positions.get(&(x, y + 1, z)));
-            let bottom = block_database::get().unwrap_block(block_positions.get(&(x, y - 1, z)));
-            let front = block_database::get().unwrap_block(block_positions.get(&(x, y, z - 1)));
-            let back = block_database::get().unwrap_block(block_positions.get(&(x, y, z + 1)));
+        for ((x, y, z), state) in blocks.iter() {
+            let block = database.get_block(state.block_type);
+            let transform = translate(&Mat4::one(), vec3(x as f32, y as f32, z as f32)) * state.transform(&block);
 
-            if block.has_sub1_scale() || !right.opaque {
-                let (mut verts, mut inds) = block.build_right_face(
-                    &Vec3i::new(x,y,z), current_stride, true);
-                vertices.append(&mut verts);
-                indices.append(&mut inds);
-                current_stride += index_stride;
-            }
-            if block.has_sub1_scale() || !left.opaque {
-                let (mut verts, mut inds) = block.build_left_face(
-                    &Vec3i::new(x,y,z), current_stride, true);
-                vertices.append(&mut verts);
-                indices.append(&mut inds);
-                current_stride += index_stride;
-            }
-            if block.has_sub1_scale() || !top.opaque {
-                let (mut verts, mut inds) = block.build_top_face(
-                    &Vec3i::new(x,y,z), current_stride, true);
-                vertices.append(&mut verts);
-                indices.append(&mut inds);
-                current_stride += index_stride;
-            }
-            if block.has_sub1_scale() || !bottom.opaque {
-                let (mut verts, mut inds) = block.build_bottom_face(
-                    &Vec3i::new(x,y,z), current_stride, true);
-                vertices.append(&mut verts);
-                indices.append(&mut inds);
-                current_stride += index_stride;
-            }
-            if block.has_sub1_scale() || !front.opaque {
-                let (mut verts, mut inds) = block.build_front_face(
-                    &Vec3i::new(x,y,z), current_stride, true);
-                vertices.append(&mut verts);
-                indices.append(&mut inds);
-                current_stride += index_stride;
-            }
-            if block.has_sub1_scale() || !back.opaque {
-                let (mut verts, mut inds) = block.build_back_face(
-                    &Vec3i::new(x,y,z), current_stride, true);
-                vertices.append(&mut verts);
-                indices.append(&mut inds);
-                current_stride += index_stride;
-            }
+            let is_culled = |face: Face| {
+                let n = transform * vec4(face.normal().x, face.normal().y, face.normal().z, 0.0);
+                database.get_block(blocks.get_type(
+                    x + n.x.round() as i32, y + n.y.round() as i32, z + n.z.round() as i32)).opaque
+            };
+            let (verts, mut inds) = block.build(&Vec3i::new(0, 0, 0), vertices.len() as u32, &is_culled);
+            vertices.extend(verts.into_iter().map(|v| {
+                let p = transform * vec4(v.position.x, v.position.y, v.position.z, 1.0);
+                let n = transform * vec4(v.normal.x, v.normal.y, v.normal.z, 0.0);
+                Vertex { position: vec3(p.x, p.y, p.z), normal: vec3(n.x, n.y, n.z), ..v }
+            }));
+            indices.append(&mut inds);
         }
 
         ChunkMesh { vertices, indices }
@@ -79,4 +57,40 @@ impl ChunkMesh {
 
     pub fn verts(&self) -> &Vec<Vertex> { &self.vertices }
     pub fn inds(&self) -> &Vec<u32> { &self.indices }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use world::block::block_state::BlockState;
+    use world::block::block_type::{BlockType, keys};
+
+    fn dirt() -> BlockState {
+        BlockState::new(block_database::get().block_type(keys::DIRT).unwrap_or(BlockType::AIR))
+    }
+
+    #[test]
+    fn air_only_chunk_has_empty_mesh() {
+        let mut blocks = BlockStorage::new();
+        blocks.set(4, 10, 4, BlockState::air());
+        let mesh = ChunkMesh::new(&blocks, LodLevel::Full);
+        assert!(mesh.verts().is_empty());
+        assert!(mesh.inds().is_empty());
+    }
+
+    #[test]
+    fn neighbouring_opaque_blocks_share_no_faces() {
+        let mut single = BlockStorage::new();
+        single.set(4, 10, 4, dirt());
+        let single = ChunkMesh::new(&single, LodLevel::Full);
+        let face = single.inds().len() / 6;
+        assert!(face > 0);
+
+        let mut pair = BlockStorage::new();
+        pair.set(4, 10, 4, dirt());
+        pair.set(5, 10, 4, dirt());
+        let pair = ChunkMesh::new(&pair, LodLevel::Full);
+        assert_eq!(pair.inds().len(), 10 * face);
+        assert!(pair.verts().iter().all(|v| (v.position.x - 4.5).abs() > 0.01 || v.normal.x.abs() < 0.5));
+    }
 }
\ No newline at end of file
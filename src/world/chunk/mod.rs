@@ -1,3 +1,5 @@
 pub mod chunk;
 pub mod chunk_mesh;
-pub mod chunk_buffer;
\ No newline at end of file
+pub mod block_storage;
+pub mod chunk_lod;
+pub mod chunk_codec;
\ No newline at end of file
@@ -2,5 +2,9 @@ pub const CHUNK_SIZE: i32 = 12;
 pub const CHUNK_AREA: i32 = CHUNK_SIZE * CHUNK_SIZE;
 pub const CHUNK_HEIGHT: i32 = 255;
 pub const CHUNK_BOTTOM: i32 = 0;
+pub const SEA_LEVEL: i32 = 100;
 
-pub const RENDER_DISTANCE: i32 = 8;
\ No newline at end of file
+pub const RENDER_DISTANCE: i32 = 8;
+pub const MIN_RENDER_DISTANCE: i32 = 2;
+pub const MAX_RENDER_DISTANCE: i32 = 32;
+pub const LOD_DISTANCES: [i32; 3] = [8, 16, 24];
\ No newline at end of file
@@ -1,5 +1,5 @@
 pub mod world;
 pub mod block;
 pub mod chunk;
-pub mod scenery;
-pub mod constants;
\ No newline at end of file
+pub mod constants;
+pub mod save;
\ No newline at end of file
@@ -0,0 +1,179 @@
+use serde::Serialize;
+use serde_json;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use world::block::block_database;
+use world::block::block_id_map::BlockIdMap;
+use world::block::block_state::{Axis, BlockState, Facing, Half};
+use world::chunk::block_storage::BlockStorage;
+use world::chunk::chunk_codec::{self, Compression};
+
+const LEVEL_FILE: &'static str = "level.json";
+const BLOCK_IDS_FILE: &'static str = "block_ids.json";
+const CHUNK_DIRECTORY: &'static str = "chunks";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelData {
+    pub seed: u32,
+    pub spawn: Option<[f32; 3]>,
+    pub ticks: u64
+}
+
+#[derive(Deserialize)]
+struct SavedState {
+    id: u16,
+    facing: Facing,
+    axis: Axis,
+    half: Half
+}
+
+#[derive(Deserialize)]
+struct SavedChunk {
+    x: i32,
+    z: i32,
+    palette: Vec<SavedState>,
+    indices: Vec<u16>
+}
+
+pub struct WorldSave {
+    directory: PathBuf,
+    ids: BlockIdMap
+}
+
+impl WorldSave {
+    pub fn open(directory: &Path) -> Result<WorldSave, String> {
+        fs::create_dir_all(directory.join(CHUNK_DIRECTORY))
+            .map_err(|e| format!("Failed to create world directory {}: {}", directory.display(), e))?;
+        let ids = BlockIdMap::load(&directory.join(BLOCK_IDS_FILE))?;
+        Ok(WorldSave { directory: directory.to_path_buf(), ids })
+    }
+
+    pub fn load_level(&self) -> Result<Option<LevelData>, String> {
+        let path = self.directory.join(LEVEL_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        read_json(&path).map(Some)
+    }
+
+    pub fn ids(&self) -> &BlockIdMap {
+        &self.ids
+    }
+
+    pub fn ids_mut(&mut self) -> &mut BlockIdMap {
+        &mut self.ids
+    }
+
+    pub fn load_chunks(&self) -> Result<HashMap<(i32, i32), Arc<BlockStorage>>, String> {
+        let directory = self.directory.join(CHUNK_DIRECTORY);
+        let entries = fs::read_dir(&directory)
+            .map_err(|e| format!("Failed to read {}: {}", directory.display(), e))?;
+        let mut chunks = HashMap::new();
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let (index, blocks) = match path.extension().and_then(|e| e.to_str()) {
+                Some("chunk") => self.load_chunk(&path)?,
+                Some("json") => self.load_legacy_chunk(&path)?,
+                _ => continue
+            };
+            if chunks.insert(index, Arc::new(blocks)).is_some() {
+                println!("Chunk {:?} is saved twice, using {}", index, path.display());
+            }
+        }
+        Ok(chunks)
+    }
+
+    fn load_chunk(&self, path: &Path) -> Result<((i32, i32), BlockStorage), String> {
+        let index = path.file_stem().and_then(|s| s.to_str()).and_then(parse_chunk_name)
+            .ok_or_else(|| format!("Invalid chunk file name {}", path.display()))?;
+        let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let blocks = chunk_codec::decode(&data, &self.ids).map_err(|e| format!("Invalid chunk {}: {}", path.display(), e))?;
+        Ok((index, blocks))
+    }
+
+    fn load_legacy_chunk(&self, path: &Path) -> Result<((i32, i32), BlockStorage), String> {
+        let database = block_database::get();
+        let saved: SavedChunk = read_json(path)?;
+        let palette = saved.palette.iter().map(|s| BlockState {
+            block_type: self.ids.block_type(s.id, &database),
+            facing: s.facing,
+            axis: s.axis,
+            half: s.half
+        }).collect();
+        let blocks = BlockStorage::from_parts(palette, saved.indices)
+            .map_err(|e| format!("Invalid chunk {}: {}", path.display(), e))?;
+        Ok(((saved.x, saved.z), blocks))
+    }
+
+    pub fn save(&mut self, level: &LevelData, chunks: &HashMap<(i32, i32), Arc<BlockStorage>>) -> Result<(), String> {
+        let directory = self.directory.join(CHUNK_DIRECTORY);
+        for (&(x, z), blocks) in chunks.iter() {
+            let data = chunk_codec::encode(blocks, &mut self.ids, Compression::Zlib);
+            write_file(&directory.join(format!("c.{}.{}.chunk", x, z)), &data)?;
+            let legacy = directory.join(format!("c.{}.{}.json", x, z));
+            if legacy.exists() {
+                fs::remove_file(&legacy).map_err(|e| format!("Failed to remove {}: {}", legacy.display(), e))?;
+            }
+        }
+        write_json(&self.directory.join(BLOCK_IDS_FILE), &self.ids)?;
+        write_json(&self.directory.join(LEVEL_FILE), level)
+    }
+}
+
+fn parse_chunk_name(name: &str) -> Option<(i32, i32)> {
+    let mut parts = name.split('.');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some("c"), Some(x), Some(z), None) => Some((x.parse().ok()?, z.parse().ok()?)),
+        _ => None
+    }
+}
+
+fn read_json<T>(path: &Path) -> Result<T, String> where for<'de> T: ::serde::Deserialize<'de> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    serde_json::from_reader(BufReader::new(file)).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<(), String> {
+    let temp = path.with_extension("tmp");
+    File::create(&temp).and_then(|mut file| file.write_all(data))
+        .map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    fs::rename(&temp, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let temp = path.with_extension("tmp");
+    {
+        let file = File::create(&temp).map_err(|e| format!("Failed to create {}: {}", temp.display(), e))?;
+        serde_json::to_writer(BufWriter::new(file), value).map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    }
+    fs::rename(&temp, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use world::block::block_type::keys;
+
+    #[test]
+    fn saves_block_ids_and_chunks_atomically() {
+        let directory = ::std::env::temp_dir().join(format!("minecraft-save-test-{}", ::std::process::id()));
+        let database = block_database::get();
+        let dirt = BlockState::new(database.block_type(keys::DIRT).unwrap());
+        let mut blocks = BlockStorage::new();
+        blocks.set(2, 3, 4, dirt);
+        let mut chunks = HashMap::new();
+        chunks.insert((-1, 2), Arc::new(blocks));
+
+        let mut save = WorldSave::open(&directory).unwrap();
+        save.save(&LevelData { seed: 7, spawn: None, ticks: 0 }, &chunks).unwrap();
+        assert!(!directory.join(BLOCK_IDS_FILE).with_extension("tmp").exists());
+
+        let reopened = WorldSave::open(&directory).unwrap();
+        assert_eq!(reopened.ids().keys(), save.ids().keys());
+        assert_eq!(reopened.load_chunks().unwrap()[&(-1, 2)].get(2, 3, 4), dirt);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
\ No newline at end of file
@@ -1,2 +0,0 @@
-pub mod lighting;
-pub mod skybox;
\ No newline at end of file
@@ -1,39 +1,45 @@
-use GL::Gl;
-
 use threadpool::ThreadPool;
 use std::sync::{Arc, Mutex, MutexGuard, mpsc};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use util::math::{Vec3, Vec2i, Vec3i};
+use world::block::block_state::BlockState;
+use world::block::block_type::BlockType;
 use world::constants::CHUNK_SIZE;
-use world::constants::RENDER_DISTANCE;
 use world::chunk::chunk::Chunk;
-use world::block::block_texture::BlockTexture;
-use shader::Shader;
-use camera::Camera;
-use world::chunk::chunk_buffer::ChunkBuffer;
+use world::chunk::block_storage::BlockStorage;
 
 pub struct World {
-    chunk_queue: Arc<Mutex<Vec<Chunk>>>,
-    active_chunks: HashMap<(i32, i32), (Chunk, ChunkBuffer)>,
-    gl: Gl,
-    block_texture: BlockTexture,
+    chunk_queue: Arc<Mutex<Vec<(u64, Chunk)>>>,
+    active_chunks: HashMap<(i32, i32), Chunk>,
+    pending_chunks: HashMap<(i32, i32), u64>,
+    next_job: u64,
+    changed_chunks: HashSet<(i32, i32)>,
+    modified_chunks: HashMap<(i32, i32), Arc<BlockStorage>>,
     player_spawn: Arc<Mutex<Option<Vec3>>>,
-    thread_pool: ThreadPool
+    thread_pool: ThreadPool,
+    render_distance: i32,
+    generate: bool
 }
 
 impl World {
-    pub fn new(gl: &Gl) -> World {
-        let mut chunk_queue: Arc<Mutex<Vec<Chunk>>> = Arc::new(Mutex::new(Vec::new()));
-        let block_texture = BlockTexture::new(gl);
+    pub fn new(render_distance: i32, threads: usize) -> World {
+        let mut chunk_queue: Arc<Mutex<Vec<(u64, Chunk)>>> = Arc::new(Mutex::new(Vec::new()));
 
-        World { chunk_queue, active_chunks: HashMap::new(),
-            gl: gl.clone(), block_texture,
+        World { chunk_queue, active_chunks: HashMap::new(), pending_chunks: HashMap::new(), next_job: 0,
+            changed_chunks: HashSet::new(),
+            modified_chunks: HashMap::new(),
             player_spawn: Arc::new(Mutex::new(None)),
-            thread_pool: ThreadPool::new(2)
+            thread_pool: ThreadPool::new(threads),
+            render_distance,
+            generate: true
         }
     }
 
+    pub fn remote(render_distance: i32, threads: usize) -> World {
+        World { generate: false, ..World::new(render_distance, threads) }
+    }
+
     pub fn initialize_chunks(&mut self) {
         let c = World::chunk_coordinates(&Vec3::new(0.0, 0.0, 0.0));
         let (xx, zz, mx, mz) = (
@@ -50,78 +56,257 @@ impl World {
                 let tx = tx.clone();
                 let mut queue = self.chunk_queue.clone();
                 let mut spawn = self.player_spawn.clone();
+                let saved = self.modified_chunks.get(&(x, z)).cloned();
+                let job = self.next_job((x, z));
                 self.thread_pool.execute(move|| {
-                    let chunk = Chunk::new(Vec3i::new(x, 0, z));
+                    let chunk = match saved {
+                        Some(blocks) => Chunk::from_blocks(Vec3i::new(x, 0, z), blocks),
+                        None => Chunk::new(Vec3i::new(x, 0, z))
+                    };
                     if chunk.index() == (0, 0) {
                         let spawn_y = chunk.get_highest_block((0, 0));
                         let mut state = spawn.lock().unwrap();
                         ::std::mem::replace(&mut *state, Some(Vec3::new(0.0, (spawn_y + 1) as f32, 0.0)));
                     }
-                    tx.send(queue.lock().unwrap().push(chunk));
+                    tx.send(queue.lock().unwrap().push((job, chunk)));
                 });
             }
         }
     }
 
+    pub fn failed_jobs(&self) -> usize {
+        self.thread_pool.panic_count()
+    }
+
     pub fn get_player_spawn(&self) -> MutexGuard<Option<Vec3>> {
         self.player_spawn.lock().unwrap()
     }
 
     pub fn build_chunks(&mut self, position: &Vec3) {
-        let c = World::chunk_coordinates(position);
-        let (xx, zz, mx, mz) = (
-            c.0 - RENDER_DISTANCE,
-            c.1 - RENDER_DISTANCE,
-            c.0 + RENDER_DISTANCE,
-            c.1 + RENDER_DISTANCE
-        );
+        self.build_chunks_around(&[*position]);
+    }
 
-        let pool = ThreadPool::new(4);
-        let (tx, rx) = mpsc::channel();
+    pub fn build_chunks_around(&mut self, positions: &[Vec3]) {
+        let centers: Vec<(i32, i32)> = positions.iter().map(World::chunk_coordinates).collect();
+        let mut indices: Vec<((i32, i32), i32)> = Vec::new();
+        for &c in centers.iter() {
+            for x in (c.0 - self.render_distance)..=(c.0 + self.render_distance) {
+                for z in (c.1 - self.render_distance)..=(c.1 + self.render_distance) {
+                    indices.push(((x, z), World::chunk_distance(c, (x, z))));
+                }
+            }
+        }
+        indices.sort();
+        indices.dedup_by_key(|&mut (index, _)| index);
+        indices.sort_by_key(|&(_, distance)| distance);
 
-        for x in xx..mx {
-            for z in zz..mz {
-                if self.active_chunks.contains_key(&(x, z)) { continue; }
-                let tx = tx.clone();
-                let mut queue = self.chunk_queue.clone();
-                self.thread_pool.execute(move|| {
-                    let chunk = Chunk::new(Vec3i::new(x, 0, z));
-                    tx.send(queue.lock().unwrap().push(chunk));
-                });
+        for ((x, z), _) in indices {
+            if self.pending_chunks.contains_key(&(x, z)) || self.active_chunks.contains_key(&(x, z)) { continue; }
+            let saved = self.modified_chunks.get(&(x, z)).cloned();
+            if saved.is_none() && !self.generate { continue; }
+            let queue = self.chunk_queue.clone();
+            let job = self.next_job((x, z));
+            self.thread_pool.execute(move|| {
+                let chunk = match saved {
+                    Some(blocks) => Chunk::from_blocks(Vec3i::new(x, 0, z), blocks),
+                    None => Chunk::new(Vec3i::new(x, 0, z))
+                };
+                queue.lock().unwrap().push((job, chunk));
+            });
+        }
+
+        let render_distance = self.render_distance;
+        self.active_chunks.retain(|&index, _| centers.iter().any(|&c| World::chunk_distance(c, index) <= render_distance));
+    }
+
+    pub fn modified_chunks(&self) -> &HashMap<(i32, i32), Arc<BlockStorage>> {
+        &self.modified_chunks
+    }
+
+    pub fn set_modified_chunks(&mut self, chunks: HashMap<(i32, i32), Arc<BlockStorage>>) {
+        self.modified_chunks = chunks;
+    }
+
+    pub fn insert_chunk(&mut self, index: (i32, i32), blocks: Arc<BlockStorage>) {
+        self.modified_chunks.insert(index, blocks.clone());
+        self.pending_chunks.remove(&index);
+        self.changed_chunks.insert(index);
+        self.active_chunks.insert(index, Chunk::from_blocks(Vec3i::new(index.0, 0, index.1), blocks));
+    }
+
+    pub fn remove_chunk(&mut self, index: &(i32, i32)) {
+        self.modified_chunks.remove(index);
+        self.pending_chunks.remove(index);
+        self.active_chunks.remove(index);
+    }
+
+    pub fn is_area_loaded(&self, position: &Vec3) -> bool {
+        self.is_loaded_within(position, self.render_distance)
+    }
+
+    pub fn is_loaded_within(&self, position: &Vec3, distance: i32) -> bool {
+        let c = World::chunk_coordinates(position);
+        for x in (c.0 - distance)..=(c.0 + distance) {
+            for z in (c.1 - distance)..=(c.1 + distance) {
+                if !self.active_chunks.contains_key(&(x, z)) { return false; }
             }
         }
+        true
     }
 
     pub fn take_chunk_from_queue(&mut self) {
-        let length = self.chunk_queue.lock().unwrap().len();
-        if length > 0 {
-            for i in 0..length {
-                if i >= self.chunk_queue.lock().unwrap().len() { break; }
-                let chunk = self.chunk_queue.lock().unwrap().swap_remove(i);
-                let buffer = ChunkBuffer::new(&self.gl, chunk.verts(), chunk.inds());
-                self.active_chunks.insert(chunk.index(), (
-                    chunk, buffer
-                ));
-            }
+        let chunks: Vec<(u64, Chunk)> = self.chunk_queue.lock().unwrap().drain(..).collect();
+        for (job, chunk) in chunks {
+            if self.pending_chunks.get(&chunk.index()) != Some(&job) { continue; }
+            self.pending_chunks.remove(&chunk.index());
+            self.changed_chunks.insert(chunk.index());
+            self.active_chunks.insert(chunk.index(), chunk);
         }
     }
 
-    pub fn render(&self, block_shader: &Shader, camera: &Camera) {
-        self.bind_block_texture(0);
-        block_shader.bind();
-        block_shader.mat_4("view", camera.view());
-        block_shader.mat_4("projection", camera.projection());
-        block_shader.int("tex", 0);
-        for (_, (chunk, buffer)) in self.active_chunks.iter() {
-            buffer.draw(block_shader, &chunk.model());
+    pub fn chunk(&self, index: &(i32, i32)) -> Option<&Chunk> {
+        self.active_chunks.get(index)
+    }
+
+    pub fn chunk_indices<'a>(&'a self) -> impl Iterator<Item = &'a (i32, i32)> + 'a {
+        self.active_chunks.keys()
+    }
+
+    pub fn take_changed_chunks(&mut self) -> Vec<(i32, i32)> {
+        self.changed_chunks.drain().collect()
+    }
+
+    pub fn block_at(&self, position: &Vec3i) -> BlockState {
+        let (index, (x, y, z)) = World::block_coordinates(position);
+        match self.active_chunks.get(&index) {
+            Some(chunk) => chunk.block(x, y, z),
+            None => BlockState::air()
+        }
+    }
+
+    pub fn set_block(&mut self, position: &Vec3i, state: BlockState) -> bool {
+        let (index, (x, y, z)) = World::block_coordinates(position);
+        let blocks = match self.active_chunks.get_mut(&index) {
+            Some(chunk) => {
+                chunk.set_block(x, y, z, state);
+                chunk.blocks()
+            },
+            None => return match self.modified_chunks.get_mut(&index) {
+                Some(blocks) => {
+                    Arc::make_mut(blocks).set(x, y, z, state);
+                    self.pending_chunks.remove(&index);
+                    true
+                },
+                None => false
+            }
+        };
+        self.modified_chunks.insert(index, blocks);
+        self.changed_chunks.insert(index);
+        true
+    }
+
+    pub fn raycast(&self, origin: &Vec3, direction: &Vec3, max_distance: f32) -> Option<(Vec3i, Vec3i)> {
+        let start = [origin.x + 0.5, origin.y + 0.5, origin.z + 0.5];
+        let dir = [direction.x, direction.y, direction.z];
+        let mut block = [start[0].floor() as i32, start[1].floor() as i32, start[2].floor() as i32];
+        let mut step = [0; 3];
+        let mut t_max = [::std::f32::INFINITY; 3];
+        let mut t_delta = [::std::f32::INFINITY; 3];
+        for i in 0..3 {
+            if dir[i] > 0.0 {
+                step[i] = 1;
+                t_max[i] = (block[i] as f32 + 1.0 - start[i]) / dir[i];
+                t_delta[i] = 1.0 / dir[i];
+            } else if dir[i] < 0.0 {
+                step[i] = -1;
+                t_max[i] = (start[i] - block[i] as f32) / -dir[i];
+                t_delta[i] = -1.0 / dir[i];
+            }
+        }
+
+        let mut normal = [0; 3];
+        let mut t = 0.0;
+        while t <= max_distance {
+            let position = Vec3i::new(block[0], block[1], block[2]);
+            if self.block_at(&position).block_type != BlockType::AIR {
+                return Some((position, Vec3i::new(normal[0], normal[1], normal[2])));
+            }
+            let axis = if t_max[0] < t_max[1] && t_max[0] < t_max[2] { 0 }
+                else if t_max[1] < t_max[2] { 1 } else { 2 };
+            block[axis] += step[axis];
+            t = t_max[axis];
+            t_max[axis] += t_delta[axis];
+            normal = [0; 3];
+            normal[axis] = -step[axis];
         }
+        None
     }
 
-    pub fn bind_block_texture(&self, loc: u32) {
-        self.block_texture.bind(loc);
+    fn next_job(&mut self, index: (i32, i32)) -> u64 {
+        self.next_job += 1;
+        self.pending_chunks.insert(index, self.next_job);
+        self.next_job
     }
 
-    fn chunk_coordinates(position: &Vec3) -> (i32, i32) {
-        (position.x as i32 / CHUNK_SIZE, position.z as i32 / CHUNK_SIZE)
+    pub fn chunk_distance(a: (i32, i32), b: (i32, i32)) -> i32 {
+        (a.0 - b.0).abs().max((a.1 - b.1).abs())
+    }
+
+    fn block_coordinates(position: &Vec3i) -> ((i32, i32), (i32, i32, i32)) {
+        let floor_div = |v: i32| if v < 0 { (v - CHUNK_SIZE + 1) / CHUNK_SIZE } else { v / CHUNK_SIZE };
+        let index = (floor_div(position.x), floor_div(position.z));
+        (index, (position.x - index.0 * CHUNK_SIZE, position.y, position.z - index.1 * CHUNK_SIZE))
+    }
+
+    pub fn chunk_coordinates(position: &Vec3) -> (i32, i32) {
+        let size = CHUNK_SIZE as f32;
+        ((position.x / size).floor() as i32, (position.z / size).floor() as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_coordinates_floor_negative_positions() {
+        let size = CHUNK_SIZE as f32;
+        assert_eq!(World::chunk_coordinates(&Vec3::new(0.0, 0.0, 0.0)), (0, 0));
+        assert_eq!(World::chunk_coordinates(&Vec3::new(size - 0.1, 0.0, size)), (0, 1));
+        assert_eq!(World::chunk_coordinates(&Vec3::new(-0.1, 0.0, -size)), (-1, -1));
+        assert_eq!(World::chunk_coordinates(&Vec3::new(-size - 0.1, 0.0, 0.5)), (-2, 0));
+    }
+
+    #[test]
+    fn stale_chunk_jobs_do_not_overwrite_edits() {
+        let mut world = World::remote(0, 1);
+        let mut chunks = HashMap::new();
+        chunks.insert((0, 0), Arc::new(BlockStorage::new()));
+        world.set_modified_chunks(chunks);
+        let position = Vec3::new(0.0, 0.0, 0.0);
+        let stone = BlockState::new(BlockType(1));
+
+        world.build_chunks(&position);
+        assert!(world.set_block(&Vec3i::new(1, 2, 3), stone));
+        while world.chunk(&(0, 0)).is_none() {
+            world.take_chunk_from_queue();
+            world.build_chunks(&position);
+        }
+        assert_eq!(world.block_at(&Vec3i::new(1, 2, 3)), stone);
+    }
+
+    #[test]
+    fn loaded_area_is_symmetric_around_negative_position() {
+        let mut world = World::new(1, 2);
+        let position = Vec3::new(-0.5, 0.0, -0.5);
+        world.build_chunks(&position);
+        while !world.is_area_loaded(&position) {
+            world.take_chunk_from_queue();
+        }
+        for &index in [(-2, -2), (-2, 0), (0, -2), (0, 0)].iter() {
+            assert!(world.chunk(&index).is_some(), "chunk {:?} missing", index);
+        }
+        assert!(world.chunk(&(1, 1)).is_none());
+        assert!(world.chunk(&(-3, -1)).is_none());
     }
 }
\ No newline at end of file